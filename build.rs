@@ -23,9 +23,11 @@ fn main() {
 #[cfg(feature = "vendored")]
 fn build_vendored(out_dir: &Path, manifest_dir: &Path) {
     // Build OTIO + shim via CMake
-    let dst = cmake::Config::new(manifest_dir.join("shim"))
-        .define("CMAKE_BUILD_TYPE", "Release")
-        .build();
+    let mut cmake_config = cmake::Config::new(manifest_dir.join("shim"));
+    cmake_config.define("CMAKE_BUILD_TYPE", "Release");
+    #[cfg(feature = "fault-injection")]
+    cmake_config.define("OTIO_FAULT_INJECTION", "ON");
+    let dst = cmake_config.build();
 
     // Link paths
     println!("cargo:rustc-link-search=native={}/lib", dst.display());
@@ -57,6 +59,8 @@ fn build_system(out_dir: &Path, manifest_dir: &Path) {
     let mut cmake_config = cmake::Config::new(manifest_dir.join("shim"));
     cmake_config.define("CMAKE_BUILD_TYPE", "Release");
     cmake_config.define("USE_SYSTEM_OTIO", "ON");
+    #[cfg(feature = "fault-injection")]
+    cmake_config.define("OTIO_FAULT_INJECTION", "ON");
 
     // Pass OTIO include paths to CMake
     let include_paths: Vec<_> = otio.include_paths.iter()
@@ -102,8 +106,14 @@ fn link_cpp_stdlib() {
 }
 
 fn generate_bindings(out_dir: &Path, manifest_dir: &Path) {
-    let bindings = bindgen::Builder::default()
-        .header(manifest_dir.join("shim/otio_shim.h").to_string_lossy())
+    let mut builder = bindgen::Builder::default()
+        .header(manifest_dir.join("shim/otio_shim.h").to_string_lossy());
+    // Expose the test hooks declared under the same define as the shim
+    if cfg!(feature = "fault-injection") {
+        builder = builder.clang_arg("-DOTIO_FAULT_INJECTION");
+    }
+
+    let bindings = builder
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .allowlist_function("otio_.*")
         .allowlist_type("Otio.*")
@@ -36,7 +36,7 @@ fi
 
 # Build the tests
 echo -e "${YELLOW}Building tests...${NC}"
-cargo build --tests 2>&1 | tail -5
+cargo build --tests --features fault-injection 2>&1 | tail -5
 
 # Find the memory test binary
 TEST_BIN=$(find target/debug/deps -name 'memory-*' -type f -perm +111 2>/dev/null | head -1)
@@ -19,9 +19,12 @@
 #include "opentimelineio/freezeFrame.h"
 #include "opentimelineio/imageSequenceReference.h"
 #include "opentimelineio/algo/editAlgorithm.h"
+#include "opentimelineio/stackAlgorithm.h"
 
+#include <algorithm>
 #include <cstring>
 #include <exception>
+#include <string>
 
 namespace otio = opentimelineio::OPENTIMELINEIO_VERSION;
 
@@ -197,40 +200,139 @@ static void* child_at_impl(Container* container, int32_t index) {
     }
 }
 
-template<typename Container, typename Child>
-static int append_child_impl(Container* container, Child* child, OtioError* err) {
+#ifdef OTIO_FAULT_INJECTION
+// One-shot fault injected into the next transfer call on this thread (tests only)
+static thread_local int g_transfer_fault = OTIO_TRANSFER_FAULT_NONE;
+
+void otio_debug_inject_transfer_fault(int fault) {
+    g_transfer_fault = fault;
+}
+
+static int take_transfer_fault() {
+    int fault = g_transfer_fault;
+    g_transfer_fault = OTIO_TRANSFER_FAULT_NONE;
+    return fault;
+}
+#else
+static int take_transfer_fault() {
+    return OTIO_TRANSFER_FAULT_NONE;
+}
+#endif
+
+// Runs `op`, which may hand `child` over to `container`, and reports through
+// `taken` whether `owned(container, child)` holds afterwards.
+//
+// The child is pinned by a local Retainer for the duration of the call, so a
+// container that retains and then releases it again cannot delete it out from
+// under the caller. The pin is dropped with take_value() so the child is never
+// deleted here: it belongs either to the container or, still, to the caller.
+template<typename Container, typename Child, typename Op, typename Owned>
+static int transfer_impl(Container* container, Child* child, int* taken,
+                         OtioError* err, Op op, Owned owned) {
+    int fault = take_transfer_fault();
+
+    if (taken) *taken = 0;
     OTIO_NULL_CHECK_ERR(container, err, -1, "Container is null");
     OTIO_NULL_CHECK_ERR(child, err, -1, "Child is null");
+
+    Retainer<Child> pin(child);
+    int result = -1;
     try {
-        otio::ErrorStatus status;
-        container->append_child(child, &status);
-        OTIO_CHECK_STATUS(status, err);
-        return 0;
+        if (fault == OTIO_TRANSFER_FAULT_BEFORE) {
+            set_error(err, 1, "Injected failure before transfer");
+        } else {
+            otio::ErrorStatus status;
+            op(container, child, &status);
+            if (otio::is_error(status)) {
+                set_error(err, 1, status.full_description.c_str());
+            } else if (fault == OTIO_TRANSFER_FAULT_AFTER) {
+                set_error(err, 1, "Injected failure after transfer");
+            } else {
+                result = 0;
+            }
+        }
     } catch (const std::exception& e) {
         set_error(err, 1, e.what());
-        return -1;
     } catch (...) {
         set_error(err, 1, "Unknown exception");
-        return -1;
     }
+    pin.take_value();
+
+    if (taken) *taken = owned(container, child) ? 1 : 0;
+    return result;
 }
 
-template<typename Container, typename Child>
-static int insert_child_impl(Container* container, int32_t index, Child* child, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(container, err, -1, "Container is null");
-    OTIO_NULL_CHECK_ERR(child, err, -1, "Child is null");
-    try {
-        otio::ErrorStatus status;
-        container->insert_child(index, child, &status);
-        OTIO_CHECK_STATUS(status, err);
-        return 0;
-    } catch (const std::exception& e) {
-        set_error(err, 1, e.what());
-        return -1;
-    } catch (...) {
-        set_error(err, 1, "Unknown exception");
-        return -1;
+// Transfer of a composable child: owned once this container is its parent.
+// A child that already belonged to another composition is not taken here.
+template<typename Container, typename Child, typename Op>
+static int transfer_child_impl(Container* container, Child* child, int* taken,
+                               OtioError* err, Op op) {
+    return transfer_impl(container, child, taken, err, op,
+        [](Container* c, Child* ch) { return ch->parent() == c; });
+}
+
+// Whether a marker or effect list holds `item`.
+template<typename T, typename U>
+static bool list_holds(const std::vector<Retainer<T>>& items, U* item) {
+    return std::any_of(items.begin(), items.end(),
+                       [item](const Retainer<T>& r) { return r.value == item; });
+}
+
+// Transfer of a marker to an item: owned once it is in the item's markers.
+template<typename Container>
+static int add_marker_impl(Container* container, otio::Marker* marker, int* taken,
+                           OtioError* err) {
+    return transfer_impl(container, marker, taken, err,
+        [](Container* c, otio::Marker* m, otio::ErrorStatus*) {
+            c->markers().push_back(m);
+        },
+        [](Container* c, otio::Marker* m) { return list_holds(c->markers(), m); });
+}
+
+// Transfer of an effect to an item: owned once it is in the item's effects.
+template<typename Container>
+static int add_effect_impl(Container* container, otio::Effect* effect, int* taken,
+                           OtioError* err) {
+    return transfer_impl(container, effect, taken, err,
+        [](Container* c, otio::Effect* e, otio::ErrorStatus*) {
+            c->effects().push_back(e);
+        },
+        [](Container* c, otio::Effect* e) { return list_holds(c->effects(), e); });
+}
+
+// Whether any of the clip's media references is `ref`.
+static bool clip_holds_reference(otio::Clip* clip, otio::MediaReference* ref) {
+    for (const auto& entry : clip->media_references()) {
+        if (entry.second == ref) return true;
     }
+    return false;
+}
+
+// Transfer of a media reference to a clip as its active reference.
+static int set_media_reference_impl(otio::Clip* clip, otio::MediaReference* ref,
+                                    int* taken, OtioError* err) {
+    return transfer_impl(clip, ref, taken, err,
+        [](otio::Clip* c, otio::MediaReference* r, otio::ErrorStatus*) {
+            c->set_media_reference(r);
+        },
+        clip_holds_reference);
+}
+
+template<typename Container, typename Child>
+static int append_child_impl(Container* container, Child* child, int* taken, OtioError* err) {
+    return transfer_child_impl(container, child, taken, err,
+        [](Container* c, Child* ch, otio::ErrorStatus* status) {
+            c->append_child(ch, status);
+        });
+}
+
+template<typename Container, typename Child>
+static int insert_child_impl(Container* container, int32_t index, Child* child,
+                             int* taken, OtioError* err) {
+    return transfer_child_impl(container, child, taken, err,
+        [index](Container* c, Child* ch, otio::ErrorStatus* status) {
+            c->insert_child(index, ch, status);
+        });
 }
 
 template<typename Container>
@@ -415,22 +517,22 @@ void otio_track_free(OtioTrack* track) {
     }
 }
 
-int otio_track_append_clip(OtioTrack* track, OtioClip* clip, OtioError* err) {
+int otio_track_append_clip(OtioTrack* track, OtioClip* clip, int* taken, OtioError* err) {
     return append_child_impl<otio::Track, otio::Clip>(
         reinterpret_cast<otio::Track*>(track),
-        reinterpret_cast<otio::Clip*>(clip), err);
+        reinterpret_cast<otio::Clip*>(clip), taken, err);
 }
 
-int otio_track_append_gap(OtioTrack* track, OtioGap* gap, OtioError* err) {
+int otio_track_append_gap(OtioTrack* track, OtioGap* gap, int* taken, OtioError* err) {
     return append_child_impl<otio::Track, otio::Gap>(
         reinterpret_cast<otio::Track*>(track),
-        reinterpret_cast<otio::Gap*>(gap), err);
+        reinterpret_cast<otio::Gap*>(gap), taken, err);
 }
 
-int otio_track_append_stack(OtioTrack* track, OtioStack* stack, OtioError* err) {
+int otio_track_append_stack(OtioTrack* track, OtioStack* stack, int* taken, OtioError* err) {
     return append_child_impl<otio::Track, otio::Stack>(
         reinterpret_cast<otio::Track*>(track),
-        reinterpret_cast<otio::Stack*>(stack), err);
+        reinterpret_cast<otio::Stack*>(stack), taken, err);
 }
 
 int32_t otio_track_children_count(OtioTrack* track) {
@@ -449,22 +551,22 @@ int otio_track_remove_child(OtioTrack* track, int32_t index, OtioError* err) {
     return remove_child_impl(reinterpret_cast<otio::Track*>(track), index, err);
 }
 
-int otio_track_insert_clip(OtioTrack* track, int32_t index, OtioClip* clip, OtioError* err) {
+int otio_track_insert_clip(OtioTrack* track, int32_t index, OtioClip* clip, int* taken, OtioError* err) {
     return insert_child_impl<otio::Track, otio::Clip>(
         reinterpret_cast<otio::Track*>(track), index,
-        reinterpret_cast<otio::Clip*>(clip), err);
+        reinterpret_cast<otio::Clip*>(clip), taken, err);
 }
 
-int otio_track_insert_gap(OtioTrack* track, int32_t index, OtioGap* gap, OtioError* err) {
+int otio_track_insert_gap(OtioTrack* track, int32_t index, OtioGap* gap, int* taken, OtioError* err) {
     return insert_child_impl<otio::Track, otio::Gap>(
         reinterpret_cast<otio::Track*>(track), index,
-        reinterpret_cast<otio::Gap*>(gap), err);
+        reinterpret_cast<otio::Gap*>(gap), taken, err);
 }
 
-int otio_track_insert_stack(OtioTrack* track, int32_t index, OtioStack* stack, OtioError* err) {
+int otio_track_insert_stack(OtioTrack* track, int32_t index, OtioStack* stack, int* taken, OtioError* err) {
     return insert_child_impl<otio::Track, otio::Stack>(
         reinterpret_cast<otio::Track*>(track), index,
-        reinterpret_cast<otio::Stack*>(stack), err);
+        reinterpret_cast<otio::Stack*>(stack), taken, err);
 }
 
 int otio_track_clear_children(OtioTrack* track, OtioError* err) {
@@ -534,14 +636,20 @@ OtioClip* otio_clip_create(const char* name, OtioTimeRange source_range) {
     )
 }
 
-int otio_clip_set_media_reference(OtioClip* clip, OtioExternalRef* ref, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_NULL_CHECK_ERR(ref, err, -1, "Reference is null");
-    OTIO_TRY_INT(err,
-        OTIO_CAST(Clip, c, clip);
-        OTIO_CAST(ExternalReference, r, ref);
-        c->set_media_reference(r);
-    )
+void otio_clip_free(OtioClip* clip) {
+    if (clip) {
+        try {
+            OTIO_CAST(Clip, c, clip);
+            Retainer<otio::Clip> retainer(c);
+        } catch (...) {
+            // Ignore exceptions during cleanup
+        }
+    }
+}
+
+int otio_clip_set_media_reference(OtioClip* clip, OtioExternalRef* ref, int* taken, OtioError* err) {
+    return set_media_reference_impl(reinterpret_cast<otio::Clip*>(clip),
+                                    reinterpret_cast<otio::ExternalReference*>(ref), taken, err);
 }
 
 char* otio_clip_get_name(OtioClip* clip) {
@@ -660,44 +768,37 @@ int otio_clip_set_active_media_reference_key(OtioClip* clip, const char* key, Ot
 }
 
 int otio_clip_add_media_reference(OtioClip* clip, const char* key,
-                                   void* ref, int32_t ref_type, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
+                                   void* ref, int32_t ref_type, int* taken, OtioError* err) {
+    if (taken) *taken = 0;
     OTIO_NULL_CHECK_ERR(key, err, -1, "Key is null");
-    OTIO_NULL_CHECK_ERR(ref, err, -1, "Reference is null");
-    try {
-        OTIO_CAST(Clip, c, clip);
-        otio::MediaReference* media_ref = nullptr;
-        switch (ref_type) {
-            case 0: // OTIO_REF_TYPE_EXTERNAL
-                media_ref = reinterpret_cast<otio::ExternalReference*>(ref);
-                break;
-            case 1: // OTIO_REF_TYPE_MISSING
-                media_ref = reinterpret_cast<otio::MissingReference*>(ref);
-                break;
-            case 2: // OTIO_REF_TYPE_GENERATOR
-                media_ref = reinterpret_cast<otio::GeneratorReference*>(ref);
-                break;
-            case 3: // OTIO_REF_TYPE_IMAGE_SEQUENCE
-                media_ref = reinterpret_cast<otio::ImageSequenceReference*>(ref);
-                break;
-            default:
-                set_error(err, 1, "Unknown reference type");
-                return -1;
-        }
-        // Get existing references and add the new one
-        auto refs = c->media_references();
-        refs[key] = media_ref;
-        // Keep the current active key
-        std::string active_key = c->active_media_reference_key();
-        c->set_media_references(refs, active_key);
-        return 0;
-    } catch (const std::exception& e) {
-        set_error(err, 1, e.what());
-        return -1;
-    } catch (...) {
-        set_error(err, 1, "Unknown exception");
-        return -1;
+    otio::MediaReference* media_ref = nullptr;
+    switch (ref_type) {
+        case 0: // OTIO_REF_TYPE_EXTERNAL
+            media_ref = reinterpret_cast<otio::ExternalReference*>(ref);
+            break;
+        case 1: // OTIO_REF_TYPE_MISSING
+            media_ref = reinterpret_cast<otio::MissingReference*>(ref);
+            break;
+        case 2: // OTIO_REF_TYPE_GENERATOR
+            media_ref = reinterpret_cast<otio::GeneratorReference*>(ref);
+            break;
+        case 3: // OTIO_REF_TYPE_IMAGE_SEQUENCE
+            media_ref = reinterpret_cast<otio::ImageSequenceReference*>(ref);
+            break;
+        default:
+            set_error(err, 1, "Unknown reference type");
+            return -1;
     }
+    std::string ref_key = key;
+    return transfer_impl(reinterpret_cast<otio::Clip*>(clip), media_ref, taken, err,
+        [&ref_key](otio::Clip* c, otio::MediaReference* r, otio::ErrorStatus* status) {
+            // Get existing references and add the new one
+            auto refs = c->media_references();
+            refs[ref_key] = r;
+            // Keep the current active key
+            c->set_media_references(refs, c->active_media_reference_key(), status);
+        },
+        clip_holds_reference);
 }
 
 int otio_clip_has_media_reference(OtioClip* clip, const char* key) {
@@ -726,6 +827,17 @@ OtioGap* otio_gap_create(OtioRationalTime duration) {
     )
 }
 
+void otio_gap_free(OtioGap* gap) {
+    if (gap) {
+        try {
+            OTIO_CAST(Gap, g, gap);
+            Retainer<otio::Gap> retainer(g);
+        } catch (...) {
+            // Ignore exceptions during cleanup
+        }
+    }
+}
+
 char* otio_gap_get_name(OtioGap* gap) {
     OTIO_NULL_CHECK(gap, nullptr);
     OTIO_TRY_PTR(
@@ -756,10 +868,14 @@ int otio_external_ref_set_available_range(OtioExternalRef* ref, OtioTimeRange ra
 }
 
 void otio_external_ref_free(OtioExternalRef* ref) {
-    // ExternalReferences are typically owned by clips via set_media_reference.
-    // This function exists for API completeness but the reference is managed
-    // by OTIO's reference counting when attached to a clip.
-    (void)ref;
+    if (ref) {
+        try {
+            OTIO_CAST(ExternalReference, r, ref);
+            Retainer<otio::ExternalReference> retainer(r);
+        } catch (...) {
+            // Ignore exceptions during cleanup
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -786,28 +902,28 @@ void otio_stack_free(OtioStack* stack) {
     }
 }
 
-int otio_stack_append_track(OtioStack* stack, OtioTrack* track, OtioError* err) {
+int otio_stack_append_track(OtioStack* stack, OtioTrack* track, int* taken, OtioError* err) {
     return append_child_impl<otio::Stack, otio::Track>(
         reinterpret_cast<otio::Stack*>(stack),
-        reinterpret_cast<otio::Track*>(track), err);
+        reinterpret_cast<otio::Track*>(track), taken, err);
 }
 
-int otio_stack_append_clip(OtioStack* stack, OtioClip* clip, OtioError* err) {
+int otio_stack_append_clip(OtioStack* stack, OtioClip* clip, int* taken, OtioError* err) {
     return append_child_impl<otio::Stack, otio::Clip>(
         reinterpret_cast<otio::Stack*>(stack),
-        reinterpret_cast<otio::Clip*>(clip), err);
+        reinterpret_cast<otio::Clip*>(clip), taken, err);
 }
 
-int otio_stack_append_gap(OtioStack* stack, OtioGap* gap, OtioError* err) {
+int otio_stack_append_gap(OtioStack* stack, OtioGap* gap, int* taken, OtioError* err) {
     return append_child_impl<otio::Stack, otio::Gap>(
         reinterpret_cast<otio::Stack*>(stack),
-        reinterpret_cast<otio::Gap*>(gap), err);
+        reinterpret_cast<otio::Gap*>(gap), taken, err);
 }
 
-int otio_stack_append_stack(OtioStack* stack, OtioStack* child, OtioError* err) {
+int otio_stack_append_stack(OtioStack* stack, OtioStack* child, int* taken, OtioError* err) {
     return append_child_impl<otio::Stack, otio::Stack>(
         reinterpret_cast<otio::Stack*>(stack),
-        reinterpret_cast<otio::Stack*>(child), err);
+        reinterpret_cast<otio::Stack*>(child), taken, err);
 }
 
 int32_t otio_stack_children_count(OtioStack* stack) {
@@ -826,28 +942,28 @@ int otio_stack_remove_child(OtioStack* stack, int32_t index, OtioError* err) {
     return remove_child_impl(reinterpret_cast<otio::Stack*>(stack), index, err);
 }
 
-int otio_stack_insert_track(OtioStack* stack, int32_t index, OtioTrack* track, OtioError* err) {
+int otio_stack_insert_track(OtioStack* stack, int32_t index, OtioTrack* track, int* taken, OtioError* err) {
     return insert_child_impl<otio::Stack, otio::Track>(
         reinterpret_cast<otio::Stack*>(stack), index,
-        reinterpret_cast<otio::Track*>(track), err);
+        reinterpret_cast<otio::Track*>(track), taken, err);
 }
 
-int otio_stack_insert_clip(OtioStack* stack, int32_t index, OtioClip* clip, OtioError* err) {
+int otio_stack_insert_clip(OtioStack* stack, int32_t index, OtioClip* clip, int* taken, OtioError* err) {
     return insert_child_impl<otio::Stack, otio::Clip>(
         reinterpret_cast<otio::Stack*>(stack), index,
-        reinterpret_cast<otio::Clip*>(clip), err);
+        reinterpret_cast<otio::Clip*>(clip), taken, err);
 }
 
-int otio_stack_insert_gap(OtioStack* stack, int32_t index, OtioGap* gap, OtioError* err) {
+int otio_stack_insert_gap(OtioStack* stack, int32_t index, OtioGap* gap, int* taken, OtioError* err) {
     return insert_child_impl<otio::Stack, otio::Gap>(
         reinterpret_cast<otio::Stack*>(stack), index,
-        reinterpret_cast<otio::Gap*>(gap), err);
+        reinterpret_cast<otio::Gap*>(gap), taken, err);
 }
 
-int otio_stack_insert_stack(OtioStack* stack, int32_t index, OtioStack* child, OtioError* err) {
+int otio_stack_insert_stack(OtioStack* stack, int32_t index, OtioStack* child, int* taken, OtioError* err) {
     return insert_child_impl<otio::Stack, otio::Stack>(
         reinterpret_cast<otio::Stack*>(stack), index,
-        reinterpret_cast<otio::Stack*>(child), err);
+        reinterpret_cast<otio::Stack*>(child), taken, err);
 }
 
 int otio_stack_clear_children(OtioStack* stack, OtioError* err) {
@@ -1377,16 +1493,16 @@ char* otio_transition_get_metadata_string(OtioTransition* transition, const char
     return get_metadata_string_impl(reinterpret_cast<otio::Transition*>(transition), key);
 }
 
-int otio_track_append_transition(OtioTrack* track, OtioTransition* transition, OtioError* err) {
+int otio_track_append_transition(OtioTrack* track, OtioTransition* transition, int* taken, OtioError* err) {
     return append_child_impl<otio::Track, otio::Transition>(
         reinterpret_cast<otio::Track*>(track),
-        reinterpret_cast<otio::Transition*>(transition), err);
+        reinterpret_cast<otio::Transition*>(transition), taken, err);
 }
 
-int otio_track_insert_transition(OtioTrack* track, int32_t index, OtioTransition* transition, OtioError* err) {
+int otio_track_insert_transition(OtioTrack* track, int32_t index, OtioTransition* transition, int* taken, OtioError* err) {
     return insert_child_impl<otio::Track, otio::Transition>(
         reinterpret_cast<otio::Track*>(track), index,
-        reinterpret_cast<otio::Transition*>(transition), err);
+        reinterpret_cast<otio::Transition*>(transition), taken, err);
 }
 
 // ----------------------------------------------------------------------------
@@ -1696,13 +1812,10 @@ OtioTimeRange otio_image_seq_ref_get_available_range(OtioImageSeqRef* ref) {
     }
 }
 
-int otio_clip_set_image_sequence_reference(OtioClip* clip, OtioImageSeqRef* ref, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto r = reinterpret_cast<otio::ImageSequenceReference*>(ref);
-        c->set_media_reference(r);
-    )
+int otio_clip_set_image_sequence_reference(OtioClip* clip, OtioImageSeqRef* ref, int* taken,
+                                           OtioError* err) {
+    return set_media_reference_impl(reinterpret_cast<otio::Clip*>(clip),
+                                    reinterpret_cast<otio::ImageSequenceReference*>(ref), taken, err);
 }
 
 void otio_image_seq_ref_set_metadata_string(OtioImageSeqRef* ref, const char* key, const char* value) {
@@ -1896,14 +2009,9 @@ char* otio_freeze_frame_get_metadata_string(OtioFreezeFrame* effect, const char*
 // Clip Marker/Effect attachment
 // ----------------------------------------------------------------------------
 
-int otio_clip_add_marker(OtioClip* clip, OtioMarker* marker, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_NULL_CHECK_ERR(marker, err, -1, "Marker is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto m = reinterpret_cast<otio::Marker*>(marker);
-        c->markers().push_back(m);
-    )
+int otio_clip_add_marker(OtioClip* clip, OtioMarker* marker, int* taken, OtioError* err) {
+    return add_marker_impl(reinterpret_cast<otio::Clip*>(clip),
+                           reinterpret_cast<otio::Marker*>(marker), taken, err);
 }
 
 int32_t otio_clip_markers_count(OtioClip* clip) {
@@ -1928,14 +2036,9 @@ OtioMarker* otio_clip_marker_at(OtioClip* clip, int32_t index) {
     }
 }
 
-int otio_clip_add_effect(OtioClip* clip, OtioEffect* effect, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_NULL_CHECK_ERR(effect, err, -1, "Effect is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto e = reinterpret_cast<otio::Effect*>(effect);
-        c->effects().push_back(e);
-    )
+int otio_clip_add_effect(OtioClip* clip, OtioEffect* effect, int* taken, OtioError* err) {
+    return add_effect_impl(reinterpret_cast<otio::Clip*>(clip),
+                           reinterpret_cast<otio::Effect*>(effect), taken, err);
 }
 
 int32_t otio_clip_effects_count(OtioClip* clip) {
@@ -1960,46 +2063,30 @@ OtioEffect* otio_clip_effect_at(OtioClip* clip, int32_t index) {
     }
 }
 
-int otio_clip_add_linear_time_warp(OtioClip* clip, OtioLinearTimeWarp* effect, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_NULL_CHECK_ERR(effect, err, -1, "LinearTimeWarp is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto e = reinterpret_cast<otio::LinearTimeWarp*>(effect);
-        c->effects().push_back(e);
-    )
+int otio_clip_add_linear_time_warp(OtioClip* clip, OtioLinearTimeWarp* effect, int* taken,
+                                   OtioError* err) {
+    return add_effect_impl(reinterpret_cast<otio::Clip*>(clip),
+                           reinterpret_cast<otio::LinearTimeWarp*>(effect), taken, err);
 }
 
-int otio_clip_set_missing_reference(OtioClip* clip, OtioMissingRef* ref, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto r = reinterpret_cast<otio::MissingReference*>(ref);
-        c->set_media_reference(r);
-    )
+int otio_clip_set_missing_reference(OtioClip* clip, OtioMissingRef* ref, int* taken, OtioError* err) {
+    return set_media_reference_impl(reinterpret_cast<otio::Clip*>(clip),
+                                    reinterpret_cast<otio::MissingReference*>(ref), taken, err);
 }
 
-int otio_clip_set_generator_reference(OtioClip* clip, OtioGeneratorRef* ref, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    OTIO_TRY_INT(err,
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        auto r = reinterpret_cast<otio::GeneratorReference*>(ref);
-        c->set_media_reference(r);
-    )
+int otio_clip_set_generator_reference(OtioClip* clip, OtioGeneratorRef* ref, int* taken,
+                                      OtioError* err) {
+    return set_media_reference_impl(reinterpret_cast<otio::Clip*>(clip),
+                                    reinterpret_cast<otio::GeneratorReference*>(ref), taken, err);
 }
 
 // ----------------------------------------------------------------------------
 // Track Marker attachment
 // ----------------------------------------------------------------------------
 
-int otio_track_add_marker(OtioTrack* track, OtioMarker* marker, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(track, err, -1, "Track is null");
-    OTIO_NULL_CHECK_ERR(marker, err, -1, "Marker is null");
-    OTIO_TRY_INT(err,
-        auto t = reinterpret_cast<otio::Track*>(track);
-        auto m = reinterpret_cast<otio::Marker*>(marker);
-        t->markers().push_back(m);
-    )
+int otio_track_add_marker(OtioTrack* track, OtioMarker* marker, int* taken, OtioError* err) {
+    return add_marker_impl(reinterpret_cast<otio::Track*>(track),
+                           reinterpret_cast<otio::Marker*>(marker), taken, err);
 }
 
 int32_t otio_track_markers_count(OtioTrack* track) {
@@ -2347,49 +2434,23 @@ OtioTimeRange otio_timeline_get_duration(OtioTimeline* tl, OtioError* err) {
 // ----------------------------------------------------------------------------
 
 int otio_track_overwrite(OtioTrack* track, OtioClip* clip,
-    OtioTimeRange range, int remove_transitions, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(track, err, -1, "Track is null");
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    try {
-        auto t = reinterpret_cast<otio::Track*>(track);
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        otio::ErrorStatus status;
-        otio::algo::overwrite(c, t, to_otio_tr(range), remove_transitions != 0, nullptr, &status);
-        if (otio::is_error(status)) {
-            set_error(err, 1, status.full_description.c_str());
-            return -1;
-        }
-        return 0;
-    } catch (const std::exception& e) {
-        set_error(err, 1, e.what());
-        return -1;
-    } catch (...) {
-        set_error(err, 1, "Unknown exception");
-        return -1;
-    }
+    OtioTimeRange range, int remove_transitions, int* taken, OtioError* err) {
+    return transfer_child_impl(
+        reinterpret_cast<otio::Track*>(track),
+        reinterpret_cast<otio::Clip*>(clip), taken, err,
+        [&](otio::Track* t, otio::Clip* c, otio::ErrorStatus* status) {
+            otio::algo::overwrite(c, t, to_otio_tr(range), remove_transitions != 0, nullptr, status);
+        });
 }
 
 int otio_track_insert_at_time(OtioTrack* track, OtioClip* clip,
-    OtioRationalTime time, int remove_transitions, OtioError* err) {
-    OTIO_NULL_CHECK_ERR(track, err, -1, "Track is null");
-    OTIO_NULL_CHECK_ERR(clip, err, -1, "Clip is null");
-    try {
-        auto t = reinterpret_cast<otio::Track*>(track);
-        auto c = reinterpret_cast<otio::Clip*>(clip);
-        otio::ErrorStatus status;
-        otio::algo::insert(c, t, to_otio_rt(time), remove_transitions != 0, nullptr, &status);
-        if (otio::is_error(status)) {
-            set_error(err, 1, status.full_description.c_str());
-            return -1;
-        }
-        return 0;
-    } catch (const std::exception& e) {
-        set_error(err, 1, e.what());
-        return -1;
-    } catch (...) {
-        set_error(err, 1, "Unknown exception");
-        return -1;
-    }
+    OtioRationalTime time, int remove_transitions, int* taken, OtioError* err) {
+    return transfer_child_impl(
+        reinterpret_cast<otio::Track*>(track),
+        reinterpret_cast<otio::Clip*>(clip), taken, err,
+        [&](otio::Track* t, otio::Clip* c, otio::ErrorStatus* status) {
+            otio::algo::insert(c, t, to_otio_rt(time), remove_transitions != 0, nullptr, status);
+        });
 }
 
 int otio_track_slice_at_time(OtioTrack* track, OtioRationalTime time,
@@ -2843,4 +2904,390 @@ void otio_clip_iterator_free(OtioClipIterator* iter) {
     delete iter;
 }
 
+// ----------------------------------------------------------------------------
+// Flattening and media-time mapping
+// ----------------------------------------------------------------------------
+
+OtioTrack* otio_timeline_flatten_tracks(OtioTimeline* tl, int32_t kind, OtioError* err) {
+    OTIO_NULL_CHECK_ERR(tl, err, nullptr, "Timeline is null");
+    try {
+        auto timeline = reinterpret_cast<otio::Timeline*>(tl);
+        auto tracks = kind == OTIO_TRACK_KIND_AUDIO
+            ? timeline->audio_tracks()
+            : timeline->video_tracks();
+        otio::ErrorStatus status;
+        auto flat = otio::flatten_stack(tracks, &status);
+        if (otio::is_error(status) || !flat) {
+            set_error(err, 1, status.full_description.c_str());
+            if (flat) {
+                Retainer<otio::Track> retainer(flat);
+            }
+            return nullptr;
+        }
+        flat->set_kind(kind == OTIO_TRACK_KIND_AUDIO
+            ? otio::Track::Kind::audio
+            : otio::Track::Kind::video);
+        Retainer<otio::Track> retainer(flat);
+        return reinterpret_cast<OtioTrack*>(retainer.take_value());
+    } catch (const std::exception& e) {
+        set_error(err, 1, e.what());
+        return nullptr;
+    } catch (...) {
+        set_error(err, 1, "Unknown exception");
+        return nullptr;
+    }
+}
+
+OtioTrack* otio_stack_flatten(OtioStack* stack, OtioError* err) {
+    OTIO_NULL_CHECK_ERR(stack, err, nullptr, "Stack is null");
+    try {
+        auto s = reinterpret_cast<otio::Stack*>(stack);
+        otio::ErrorStatus status;
+        auto flat = otio::flatten_stack(s, &status);
+        if (otio::is_error(status) || !flat) {
+            set_error(err, 1, status.full_description.c_str());
+            if (flat) {
+                Retainer<otio::Track> retainer(flat);
+            }
+            return nullptr;
+        }
+        Retainer<otio::Track> retainer(flat);
+        return reinterpret_cast<OtioTrack*>(retainer.take_value());
+    } catch (const std::exception& e) {
+        set_error(err, 1, e.what());
+        return nullptr;
+    } catch (...) {
+        set_error(err, 1, "Unknown exception");
+        return nullptr;
+    }
+}
+
+template<typename T>
+static OtioTimeRange composition_range_in_parent_impl(T* comp, OtioError* err) {
+    OtioTimeRange zero = {OtioRationalTime{0, 1}, OtioRationalTime{0, 1}};
+    OTIO_NULL_CHECK_ERR(comp, err, zero, "Composition is null");
+    try {
+        otio::ErrorStatus status;
+        auto range = comp->range_in_parent(&status);
+        if (otio::is_error(status)) {
+            set_error(err, 1, status.full_description.c_str());
+            return zero;
+        }
+        return OtioTimeRange{
+            OtioRationalTime{range.start_time().value(), range.start_time().rate()},
+            OtioRationalTime{range.duration().value(), range.duration().rate()}
+        };
+    } catch (const std::exception& e) {
+        set_error(err, 1, e.what());
+        return zero;
+    } catch (...) {
+        set_error(err, 1, "Unknown exception");
+        return zero;
+    }
+}
+
+OtioTimeRange otio_stack_range_in_parent(OtioStack* stack, OtioError* err) {
+    return composition_range_in_parent_impl(reinterpret_cast<otio::Stack*>(stack), err);
+}
+
+OtioTimeRange otio_track_range_in_parent(OtioTrack* track, OtioError* err) {
+    return composition_range_in_parent_impl(reinterpret_cast<otio::Track*>(track), err);
+}
+
+OtioTimeRange otio_clip_trimmed_range(OtioClip* clip, OtioError* err) {
+    OtioTimeRange zero = {OtioRationalTime{0, 1}, OtioRationalTime{0, 1}};
+    OTIO_NULL_CHECK_ERR(clip, err, zero, "Clip is null");
+    try {
+        OTIO_CAST(Clip, c, clip);
+        otio::ErrorStatus status;
+        auto range = c->trimmed_range(&status);
+        if (otio::is_error(status)) {
+            set_error(err, static_cast<int>(status.outcome), status.details.c_str());
+            return zero;
+        }
+        return OtioTimeRange{
+            OtioRationalTime{range.start_time().value(), range.start_time().rate()},
+            OtioRationalTime{range.duration().value(), range.duration().rate()}
+        };
+    } catch (const std::exception& e) {
+        set_error(err, 1, e.what());
+        return zero;
+    } catch (...) {
+        set_error(err, 1, "Unknown exception");
+        return zero;
+    }
+}
+
+char* otio_clip_get_target_url(OtioClip* clip) {
+    OTIO_NULL_CHECK(clip, nullptr);
+    OTIO_TRY_PTR(
+        OTIO_CAST(Clip, c, clip);
+        auto ref = dynamic_cast<otio::ExternalReference*>(c->media_reference());
+        if (!ref) return nullptr;
+        return safe_strdup(ref->target_url());
+    )
+}
+
+int32_t otio_clip_get_media_reference_type(OtioClip* clip) {
+    if (!clip) return -1;
+    try {
+        OTIO_CAST(Clip, c, clip);
+        auto ref = c->media_reference();
+        if (dynamic_cast<otio::ExternalReference*>(ref)) return OTIO_REF_TYPE_EXTERNAL;
+        if (dynamic_cast<otio::MissingReference*>(ref)) return OTIO_REF_TYPE_MISSING;
+        if (dynamic_cast<otio::GeneratorReference*>(ref)) return OTIO_REF_TYPE_GENERATOR;
+        if (dynamic_cast<otio::ImageSequenceReference*>(ref)) return OTIO_REF_TYPE_IMAGE_SEQUENCE;
+        return -1;
+    } catch (...) {
+        return -1;
+    }
+}
+
+char* otio_clip_get_image_sequence_url(OtioClip* clip) {
+    OTIO_NULL_CHECK(clip, nullptr);
+    OTIO_TRY_PTR(
+        OTIO_CAST(Clip, c, clip);
+        auto ref = dynamic_cast<otio::ImageSequenceReference*>(c->media_reference());
+        if (!ref) return nullptr;
+        // Same layout as ImageSequenceReference::target_url_for_image_number
+        std::string url = ref->target_url_base();
+        if (!url.empty() && url.back() != '/') url += '/';
+        url += ref->name_prefix();
+        int padding = ref->frame_zero_padding();
+        url += padding > 0 ? "%0" + std::to_string(padding) + "d" : "%d";
+        url += ref->name_suffix();
+        return safe_strdup(url);
+    )
+}
+
+double otio_clip_get_time_scalar(OtioClip* clip) {
+    if (!clip) return 1.0;
+    try {
+        OTIO_CAST(Clip, c, clip);
+        double scalar = 1.0;
+        for (auto& effect : c->effects()) {
+            if (auto warp = dynamic_cast<otio::LinearTimeWarp*>(effect.value)) {
+                scalar *= warp->time_scalar();
+            }
+        }
+        return scalar;
+    } catch (...) {
+        return 1.0;
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Metadata namespace migration
+// ----------------------------------------------------------------------------
+
+struct OtioMetadataMigrationEntry {
+    std::string schema;
+    std::string object_name;
+    std::string from_key;
+    std::string to_key;
+    int32_t outcome;
+};
+
+struct OtioMetadataMigrationReport {
+    std::vector<OtioMetadataMigrationEntry> entries;
+};
+
+struct MetadataMigrationRule {
+    std::string from_key;
+    std::string to_key;
+};
+
+// Check whether `src` can be deep-merged into `dst` without replacing any
+// existing value: keys present in both must be dictionaries on both sides
+static bool can_merge_dictionaries(const otio::AnyDictionary& dst,
+                                   const otio::AnyDictionary& src) {
+    for (const auto& entry : src) {
+        auto it = dst.find(entry.first);
+        if (it == dst.end()) continue;
+        if (it->second.type() != typeid(otio::AnyDictionary) ||
+            entry.second.type() != typeid(otio::AnyDictionary)) {
+            return false;
+        }
+        if (!can_merge_dictionaries(
+                std::any_cast<const otio::AnyDictionary&>(it->second),
+                std::any_cast<const otio::AnyDictionary&>(entry.second))) {
+            return false;
+        }
+    }
+    return true;
+}
+
+// Deep-merge `src` into `dst`; callers must check can_merge_dictionaries first
+static void merge_dictionaries(otio::AnyDictionary& dst, otio::AnyDictionary& src) {
+    for (auto& entry : src) {
+        auto it = dst.find(entry.first);
+        if (it == dst.end()) {
+            dst[entry.first] = std::move(entry.second);
+        } else {
+            merge_dictionaries(
+                std::any_cast<otio::AnyDictionary&>(it->second),
+                std::any_cast<otio::AnyDictionary&>(entry.second));
+        }
+    }
+}
+
+// Apply every rule, in order, to a single object's top-level metadata
+static void migrate_object_metadata(
+    otio::SerializableObjectWithMetadata* obj,
+    const std::vector<MetadataMigrationRule>& rules,
+    int32_t conflict_policy,
+    OtioMetadataMigrationReport* report
+) {
+    auto& meta = obj->metadata();
+    for (const auto& rule : rules) {
+        if (rule.from_key == rule.to_key) continue;
+        auto it = meta.find(rule.from_key);
+        if (it == meta.end()) continue;
+
+        int32_t outcome = OTIO_MIGRATION_OUTCOME_RENAMED;
+        auto dest = meta.find(rule.to_key);
+        if (dest != meta.end()) {
+            outcome = OTIO_MIGRATION_OUTCOME_SKIPPED_CONFLICT;
+            if (conflict_policy == OTIO_MIGRATION_CONFLICT_OVERWRITE) {
+                outcome = OTIO_MIGRATION_OUTCOME_OVERWROTE;
+            } else if (conflict_policy == OTIO_MIGRATION_CONFLICT_MERGE &&
+                       dest->second.type() == typeid(otio::AnyDictionary) &&
+                       it->second.type() == typeid(otio::AnyDictionary) &&
+                       can_merge_dictionaries(
+                           std::any_cast<const otio::AnyDictionary&>(dest->second),
+                           std::any_cast<const otio::AnyDictionary&>(it->second))) {
+                outcome = OTIO_MIGRATION_OUTCOME_MERGED;
+            }
+        }
+
+        if (outcome == OTIO_MIGRATION_OUTCOME_MERGED) {
+            merge_dictionaries(
+                std::any_cast<otio::AnyDictionary&>(dest->second),
+                std::any_cast<otio::AnyDictionary&>(it->second));
+            meta.erase(it);
+        } else if (outcome != OTIO_MIGRATION_OUTCOME_SKIPPED_CONFLICT) {
+            std::any value = std::move(it->second);
+            meta.erase(it);
+            meta[rule.to_key] = std::move(value);
+        }
+
+        report->entries.push_back(OtioMetadataMigrationEntry{
+            obj->schema_name(), obj->name(), rule.from_key, rule.to_key, outcome
+        });
+    }
+}
+
+// Walk the object graph depth-first, migrating every object that carries metadata
+static void migrate_metadata_recursive(
+    otio::SerializableObjectWithMetadata* obj,
+    const std::vector<MetadataMigrationRule>& rules,
+    int32_t conflict_policy,
+    OtioMetadataMigrationReport* report
+) {
+    if (!obj) return;
+    migrate_object_metadata(obj, rules, conflict_policy, report);
+
+    if (auto timeline = dynamic_cast<otio::Timeline*>(obj)) {
+        migrate_metadata_recursive(timeline->tracks(), rules, conflict_policy, report);
+        return;
+    }
+    if (auto item = dynamic_cast<otio::Item*>(obj)) {
+        for (auto& marker : item->markers()) {
+            migrate_metadata_recursive(marker.value, rules, conflict_policy, report);
+        }
+        for (auto& effect : item->effects()) {
+            migrate_metadata_recursive(effect.value, rules, conflict_policy, report);
+        }
+    }
+    if (auto clip = dynamic_cast<otio::Clip*>(obj)) {
+        for (const auto& pair : clip->media_references()) {
+            migrate_metadata_recursive(pair.second, rules, conflict_policy, report);
+        }
+    }
+    if (auto comp = dynamic_cast<otio::Composition*>(obj)) {
+        for (auto& child : comp->children()) {
+            migrate_metadata_recursive(child.value, rules, conflict_policy, report);
+        }
+    }
+}
+
+OtioMetadataMigrationReport* otio_timeline_migrate_metadata(
+    OtioTimeline* tl,
+    const char** from_keys,
+    const char** to_keys,
+    int32_t count,
+    int32_t conflict_policy,
+    OtioError* err
+) {
+    OTIO_NULL_CHECK_ERR(tl, err, nullptr, "Timeline is null");
+
+    // Build rule list from parallel arrays
+    std::vector<MetadataMigrationRule> rules;
+    if (from_keys && to_keys && count > 0) {
+        for (int32_t i = 0; i < count; i++) {
+            if (from_keys[i] && to_keys[i]) {
+                rules.push_back(MetadataMigrationRule{from_keys[i], to_keys[i]});
+            }
+        }
+    }
+
+    OtioMetadataMigrationReport* report = nullptr;
+    try {
+        report = new OtioMetadataMigrationReport();
+        migrate_metadata_recursive(
+            reinterpret_cast<otio::Timeline*>(tl), rules, conflict_policy, report);
+        return report;
+    } catch (const std::exception& e) {
+        delete report;
+        set_error(err, 1, e.what());
+        return nullptr;
+    } catch (...) {
+        delete report;
+        set_error(err, 1, "Unknown exception");
+        return nullptr;
+    }
+}
+
+static const OtioMetadataMigrationEntry* migration_entry_at(
+    OtioMetadataMigrationReport* report, int32_t index) {
+    if (!report || index < 0 || static_cast<size_t>(index) >= report->entries.size()) {
+        return nullptr;
+    }
+    return &report->entries[index];
+}
+
+int32_t otio_migration_report_count(OtioMetadataMigrationReport* report) {
+    if (!report) return 0;
+    return static_cast<int32_t>(report->entries.size());
+}
+
+int32_t otio_migration_report_outcome_at(OtioMetadataMigrationReport* report, int32_t index) {
+    auto entry = migration_entry_at(report, index);
+    return entry ? entry->outcome : -1;
+}
+
+char* otio_migration_report_schema_at(OtioMetadataMigrationReport* report, int32_t index) {
+    auto entry = migration_entry_at(report, index);
+    return entry ? safe_strdup(entry->schema) : nullptr;
+}
+
+char* otio_migration_report_object_name_at(OtioMetadataMigrationReport* report, int32_t index) {
+    auto entry = migration_entry_at(report, index);
+    return entry ? safe_strdup(entry->object_name) : nullptr;
+}
+
+char* otio_migration_report_from_key_at(OtioMetadataMigrationReport* report, int32_t index) {
+    auto entry = migration_entry_at(report, index);
+    return entry ? safe_strdup(entry->from_key) : nullptr;
+}
+
+char* otio_migration_report_to_key_at(OtioMetadataMigrationReport* report, int32_t index) {
+    auto entry = migration_entry_at(report, index);
+    return entry ? safe_strdup(entry->to_key) : nullptr;
+}
+
+void otio_migration_report_free(OtioMetadataMigrationReport* report) {
+    delete report;
+}
+
 } // extern "C"
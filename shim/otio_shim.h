@@ -35,6 +35,26 @@ typedef struct {
     OtioRationalTime duration;
 } OtioTimeRange;
 
+// Ownership transfer
+//
+// Functions that hand a child to a container (append, insert, overwrite,
+// add_marker, set_media_reference, ...) return 0 on success and -1 on error as
+// usual, and additionally report through `taken` whether the container now
+// holds the child:
+//   *taken == 1  the container owns the child; the caller must NOT free it
+//   *taken == 0  the caller still owns the child and must free it
+// The two are independent: a call can fail after the child was retained.
+// The child is never deleted by a transfer call, whatever the outcome.
+
+// Test hook: make the next transfer call on this thread fail. The hook is
+// only compiled in with OTIO_FAULT_INJECTION (the `fault-injection` feature).
+#define OTIO_TRANSFER_FAULT_NONE   0
+#define OTIO_TRANSFER_FAULT_BEFORE 1  // Fail before the child is handed over
+#define OTIO_TRANSFER_FAULT_AFTER  2  // Fail after the container retained it
+#ifdef OTIO_FAULT_INJECTION
+void otio_debug_inject_transfer_fault(int fault);
+#endif
+
 // Timeline
 OtioTimeline* otio_timeline_create(const char* name);
 void otio_timeline_free(OtioTimeline* tl);
@@ -51,12 +71,14 @@ void otio_track_free(OtioTrack* track);
 
 // Clips
 OtioClip* otio_clip_create(const char* name, OtioTimeRange source_range);
-int otio_clip_set_media_reference(OtioClip* clip, OtioExternalRef* ref, OtioError* err);
-int otio_track_append_clip(OtioTrack* track, OtioClip* clip, OtioError* err);
+void otio_clip_free(OtioClip* clip);
+int otio_clip_set_media_reference(OtioClip* clip, OtioExternalRef* ref, int* taken, OtioError* err);
+int otio_track_append_clip(OtioTrack* track, OtioClip* clip, int* taken, OtioError* err);
 
 // Gaps
 OtioGap* otio_gap_create(OtioRationalTime duration);
-int otio_track_append_gap(OtioTrack* track, OtioGap* gap, OtioError* err);
+void otio_gap_free(OtioGap* gap);
+int otio_track_append_gap(OtioTrack* track, OtioGap* gap, int* taken, OtioError* err);
 
 // Media references
 OtioExternalRef* otio_external_ref_create(const char* target_url);
@@ -124,16 +146,16 @@ char* otio_external_ref_get_metadata_string(OtioExternalRef* ref, const char* ke
 // Stack (composition for nested structures)
 OtioStack* otio_stack_create(const char* name);
 void otio_stack_free(OtioStack* stack);
-int otio_stack_append_track(OtioStack* stack, OtioTrack* track, OtioError* err);
-int otio_stack_append_clip(OtioStack* stack, OtioClip* clip, OtioError* err);
-int otio_stack_append_gap(OtioStack* stack, OtioGap* gap, OtioError* err);
-int otio_stack_append_stack(OtioStack* stack, OtioStack* child, OtioError* err);
+int otio_stack_append_track(OtioStack* stack, OtioTrack* track, int* taken, OtioError* err);
+int otio_stack_append_clip(OtioStack* stack, OtioClip* clip, int* taken, OtioError* err);
+int otio_stack_append_gap(OtioStack* stack, OtioGap* gap, int* taken, OtioError* err);
+int otio_stack_append_stack(OtioStack* stack, OtioStack* child, int* taken, OtioError* err);
 
 // Timeline stack accessor
 OtioStack* otio_timeline_get_tracks(OtioTimeline* tl);
 
 // Track can also contain stacks (for versioning/alternatives)
-int otio_track_append_stack(OtioTrack* track, OtioStack* stack, OtioError* err);
+int otio_track_append_stack(OtioTrack* track, OtioStack* stack, int* taken, OtioError* err);
 
 // Child type enumeration (returned by child_type functions)
 // 0 = Clip, 1 = Gap, 2 = Stack, 3 = Track, -1 = Unknown/Error
@@ -184,7 +206,7 @@ int otio_clip_set_active_media_reference_key(OtioClip* clip, const char* key, Ot
 
 // Add media reference with key
 int otio_clip_add_media_reference(OtioClip* clip, const char* key,
-                                   void* ref, int32_t ref_type, OtioError* err);
+                                   void* ref, int32_t ref_type, int* taken, OtioError* err);
 
 // Check if clip has a media reference for the given key
 int otio_clip_has_media_reference(OtioClip* clip, const char* key);
@@ -197,9 +219,9 @@ int otio_clip_has_media_reference(OtioClip* clip, const char* key);
 
 // Track modification operations
 int otio_track_remove_child(OtioTrack* track, int32_t index, OtioError* err);
-int otio_track_insert_clip(OtioTrack* track, int32_t index, OtioClip* clip, OtioError* err);
-int otio_track_insert_gap(OtioTrack* track, int32_t index, OtioGap* gap, OtioError* err);
-int otio_track_insert_stack(OtioTrack* track, int32_t index, OtioStack* stack, OtioError* err);
+int otio_track_insert_clip(OtioTrack* track, int32_t index, OtioClip* clip, int* taken, OtioError* err);
+int otio_track_insert_gap(OtioTrack* track, int32_t index, OtioGap* gap, int* taken, OtioError* err);
+int otio_track_insert_stack(OtioTrack* track, int32_t index, OtioStack* stack, int* taken, OtioError* err);
 int otio_track_clear_children(OtioTrack* track, OtioError* err);
 
 // NeighborGapPolicy constants
@@ -220,10 +242,10 @@ OtioNeighbors otio_track_neighbors_of(OtioTrack* track, int32_t child_index,
 
 // Stack modification operations
 int otio_stack_remove_child(OtioStack* stack, int32_t index, OtioError* err);
-int otio_stack_insert_track(OtioStack* stack, int32_t index, OtioTrack* track, OtioError* err);
-int otio_stack_insert_clip(OtioStack* stack, int32_t index, OtioClip* clip, OtioError* err);
-int otio_stack_insert_gap(OtioStack* stack, int32_t index, OtioGap* gap, OtioError* err);
-int otio_stack_insert_stack(OtioStack* stack, int32_t index, OtioStack* child, OtioError* err);
+int otio_stack_insert_track(OtioStack* stack, int32_t index, OtioTrack* track, int* taken, OtioError* err);
+int otio_stack_insert_clip(OtioStack* stack, int32_t index, OtioClip* clip, int* taken, OtioError* err);
+int otio_stack_insert_gap(OtioStack* stack, int32_t index, OtioGap* gap, int* taken, OtioError* err);
+int otio_stack_insert_stack(OtioStack* stack, int32_t index, OtioStack* child, int* taken, OtioError* err);
 int otio_stack_clear_children(OtioStack* stack, OtioError* err);
 
 // ----------------------------------------------------------------------------
@@ -273,8 +295,8 @@ void otio_transition_set_metadata_string(OtioTransition* transition, const char*
 char* otio_transition_get_metadata_string(OtioTransition* transition, const char* key);
 
 // Track can also contain transitions
-int otio_track_append_transition(OtioTrack* track, OtioTransition* transition, OtioError* err);
-int otio_track_insert_transition(OtioTrack* track, int32_t index, OtioTransition* transition, OtioError* err);
+int otio_track_append_transition(OtioTrack* track, OtioTransition* transition, int* taken, OtioError* err);
+int otio_track_insert_transition(OtioTrack* track, int32_t index, OtioTransition* transition, int* taken, OtioError* err);
 
 // Child type for transitions
 #define OTIO_CHILD_TYPE_TRANSITION 4
@@ -340,7 +362,8 @@ int otio_image_seq_ref_set_available_range(OtioImageSeqRef* ref, OtioTimeRange r
 OtioTimeRange otio_image_seq_ref_get_available_range(OtioImageSeqRef* ref);
 
 // Clip integration
-int otio_clip_set_image_sequence_reference(OtioClip* clip, OtioImageSeqRef* ref, OtioError* err);
+int otio_clip_set_image_sequence_reference(OtioClip* clip, OtioImageSeqRef* ref, int* taken,
+                                           OtioError* err);
 
 // Metadata
 void otio_image_seq_ref_set_metadata_string(OtioImageSeqRef* ref, const char* key, const char* value);
@@ -392,26 +415,28 @@ char* otio_freeze_frame_get_metadata_string(OtioFreezeFrame* effect, const char*
 // Clip Marker/Effect attachment
 // ----------------------------------------------------------------------------
 
-int otio_clip_add_marker(OtioClip* clip, OtioMarker* marker, OtioError* err);
+int otio_clip_add_marker(OtioClip* clip, OtioMarker* marker, int* taken, OtioError* err);
 int32_t otio_clip_markers_count(OtioClip* clip);
 OtioMarker* otio_clip_marker_at(OtioClip* clip, int32_t index);
 
-int otio_clip_add_effect(OtioClip* clip, OtioEffect* effect, OtioError* err);
+int otio_clip_add_effect(OtioClip* clip, OtioEffect* effect, int* taken, OtioError* err);
 int32_t otio_clip_effects_count(OtioClip* clip);
 OtioEffect* otio_clip_effect_at(OtioClip* clip, int32_t index);
 
 // Also support LinearTimeWarp as effect
-int otio_clip_add_linear_time_warp(OtioClip* clip, OtioLinearTimeWarp* effect, OtioError* err);
+int otio_clip_add_linear_time_warp(OtioClip* clip, OtioLinearTimeWarp* effect, int* taken,
+                                   OtioError* err);
 
 // Set media reference variants
-int otio_clip_set_missing_reference(OtioClip* clip, OtioMissingRef* ref, OtioError* err);
-int otio_clip_set_generator_reference(OtioClip* clip, OtioGeneratorRef* ref, OtioError* err);
+int otio_clip_set_missing_reference(OtioClip* clip, OtioMissingRef* ref, int* taken, OtioError* err);
+int otio_clip_set_generator_reference(OtioClip* clip, OtioGeneratorRef* ref, int* taken,
+                                      OtioError* err);
 
 // ----------------------------------------------------------------------------
 // Track Marker attachment
 // ----------------------------------------------------------------------------
 
-int otio_track_add_marker(OtioTrack* track, OtioMarker* marker, OtioError* err);
+int otio_track_add_marker(OtioTrack* track, OtioMarker* marker, int* taken, OtioError* err);
 int32_t otio_track_markers_count(OtioTrack* track);
 OtioMarker* otio_track_marker_at(OtioTrack* track, int32_t index);
 
@@ -459,13 +484,13 @@ OtioTimeRange otio_timeline_get_duration(OtioTimeline* tl, OtioError* err);
 // ----------------------------------------------------------------------------
 
 // Overwrite: Replace content in track at specified range
-// Returns 0 on success, -1 on error
+// Returns 0 on success, -1 on error; see "Ownership transfer" for `taken`
 int otio_track_overwrite(OtioTrack* track, OtioClip* clip,
-    OtioTimeRange range, int remove_transitions, OtioError* err);
+    OtioTimeRange range, int remove_transitions, int* taken, OtioError* err);
 
 // Insert: Insert item at specific time, shifting subsequent items
 int otio_track_insert_at_time(OtioTrack* track, OtioClip* clip,
-    OtioRationalTime time, int remove_transitions, OtioError* err);
+    OtioRationalTime time, int remove_transitions, int* taken, OtioError* err);
 
 // Slice: Split composition at time point
 int otio_track_slice_at_time(OtioTrack* track, OtioRationalTime time,
@@ -578,6 +603,81 @@ OtioClip* otio_clip_iterator_next(OtioClipIterator* iter);
 void otio_clip_iterator_reset(OtioClipIterator* iter);
 void otio_clip_iterator_free(OtioClipIterator* iter);
 
+// ----------------------------------------------------------------------------
+// Flattening and media-time mapping
+// ----------------------------------------------------------------------------
+
+// Flatten all tracks of the given kind (OTIO_TRACK_KIND_*) into a new standalone
+// track; higher tracks win where items overlap. Caller owns the returned track.
+OtioTrack* otio_timeline_flatten_tracks(OtioTimeline* tl, int32_t kind, OtioError* err);
+
+// Flatten the tracks of a (nested) stack into a new standalone track; higher
+// tracks win where items overlap. Fails if the stack has non-track children.
+// Caller owns the returned track.
+OtioTrack* otio_stack_flatten(OtioStack* stack, OtioError* err);
+
+// Range of a nested composition within its parent
+OtioTimeRange otio_stack_range_in_parent(OtioStack* stack, OtioError* err);
+OtioTimeRange otio_track_range_in_parent(OtioTrack* track, OtioError* err);
+
+// Trimmed range of a clip in media time (source range, or available range if unset)
+OtioTimeRange otio_clip_trimmed_range(OtioClip* clip, OtioError* err);
+
+// Target URL of the clip's active media reference (caller must free with otio_free_string)
+// Returns NULL if the active reference is not an ExternalReference
+char* otio_clip_get_target_url(OtioClip* clip);
+
+// Type of the clip's active media reference (OTIO_REF_TYPE_*)
+// Returns -1 if the clip has no media reference or it is of another type
+int32_t otio_clip_get_media_reference_type(OtioClip* clip);
+
+// printf-style URL of the clip's active ImageSequenceReference, e.g.
+// "file:///shots/shot.%04d.exr" (caller must free with otio_free_string)
+// Returns NULL if the active reference is not an ImageSequenceReference
+char* otio_clip_get_image_sequence_url(OtioClip* clip);
+
+// Combined speed of the clip's LinearTimeWarp/FreezeFrame effects (1.0 if none)
+double otio_clip_get_time_scalar(OtioClip* clip);
+
+// ----------------------------------------------------------------------------
+// Metadata namespace migration
+// ----------------------------------------------------------------------------
+
+typedef struct OtioMetadataMigrationReport OtioMetadataMigrationReport;
+
+// Conflict policy when the destination key already exists
+#define OTIO_MIGRATION_CONFLICT_SKIP      0
+#define OTIO_MIGRATION_CONFLICT_OVERWRITE 1  // Replaces the whole destination value
+#define OTIO_MIGRATION_CONFLICT_MERGE     2  // Deep-merges dictionaries, else skips
+
+// Outcome of a single key migration (returned by report accessors)
+#define OTIO_MIGRATION_OUTCOME_RENAMED          0
+#define OTIO_MIGRATION_OUTCOME_OVERWROTE        1
+#define OTIO_MIGRATION_OUTCOME_SKIPPED_CONFLICT 2
+#define OTIO_MIGRATION_OUTCOME_MERGED           3
+
+// Move top-level metadata keys across the whole timeline graph (timeline,
+// compositions, items, markers, effects, media references).
+// from_keys and to_keys are parallel arrays of length count; rules are
+// applied in order on each object. Caller must free the report.
+OtioMetadataMigrationReport* otio_timeline_migrate_metadata(
+    OtioTimeline* tl,
+    const char** from_keys,
+    const char** to_keys,
+    int32_t count,
+    int32_t conflict_policy,
+    OtioError* err
+);
+
+// Report accessors (strings - caller must free with otio_free_string)
+int32_t otio_migration_report_count(OtioMetadataMigrationReport* report);
+int32_t otio_migration_report_outcome_at(OtioMetadataMigrationReport* report, int32_t index);
+char* otio_migration_report_schema_at(OtioMetadataMigrationReport* report, int32_t index);
+char* otio_migration_report_object_name_at(OtioMetadataMigrationReport* report, int32_t index);
+char* otio_migration_report_from_key_at(OtioMetadataMigrationReport* report, int32_t index);
+char* otio_migration_report_to_key_at(OtioMetadataMigrationReport* report, int32_t index);
+void otio_migration_report_free(OtioMetadataMigrationReport* report);
+
 #ifdef __cplusplus
 }
 #endif
@@ -110,6 +110,64 @@ impl ClipRef<'_> {
         Ok(time_range_from_ffi(&range))
     }
 
+    /// Get the trimmed range of this clip in media time.
+    ///
+    /// This is the `source_range` if set, otherwise the available range of
+    /// the media reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither range is available.
+    pub fn trimmed_range(&self) -> Result<TimeRange> {
+        let mut err = macros::ffi_error!();
+        let range = unsafe { ffi::otio_clip_trimmed_range(self.ptr, &mut err) };
+        if err.code != 0 {
+            return Err(OtioError::from(err));
+        }
+        Ok(time_range_from_ffi(&range))
+    }
+
+    /// Get the target URL of this clip's active media reference.
+    ///
+    /// Returns `None` if the active reference is not an external reference.
+    #[must_use]
+    pub fn target_url(&self) -> Option<String> {
+        let ptr = unsafe { ffi::otio_clip_get_target_url(self.ptr) };
+        if ptr.is_null() {
+            return None;
+        }
+        Some(ffi_string_to_rust(ptr))
+    }
+
+    /// Get the URL pattern of this clip's active image sequence reference.
+    ///
+    /// The frame number is a printf-style placeholder, e.g.
+    /// `"file:///shots/shot.%04d.exr"`. Returns `None` if the active
+    /// reference is not an image sequence reference.
+    #[must_use]
+    pub fn image_sequence_url(&self) -> Option<String> {
+        let ptr = unsafe { ffi::otio_clip_get_image_sequence_url(self.ptr) };
+        if ptr.is_null() {
+            return None;
+        }
+        Some(ffi_string_to_rust(ptr))
+    }
+
+    /// Get the type of this clip's active media reference (`OTIO_REF_TYPE_*`),
+    /// or -1 if it has none.
+    pub(crate) fn media_reference_type(&self) -> i32 {
+        unsafe { ffi::otio_clip_get_media_reference_type(self.ptr) }
+    }
+
+    /// Get the playback speed of this clip.
+    ///
+    /// This is the product of the time scalars of all linear time warp and
+    /// freeze frame effects on the clip, or 1.0 if there are none.
+    #[must_use]
+    pub fn time_scalar(&self) -> f64 {
+        unsafe { ffi::otio_clip_get_time_scalar(self.ptr) }
+    }
+
     /// Get the parent composition of this clip.
     ///
     /// Returns `None` if the clip is not attached to a composition.
@@ -361,6 +419,50 @@ impl StackRef<'_> {
     pub fn children(&self) -> StackChildIter<'_> {
         StackChildIter::new(self.ptr)
     }
+
+    /// Get the range of this stack within its parent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stack has no parent or the range cannot be computed.
+    pub fn range_in_parent(&self) -> Result<TimeRange> {
+        let mut err = macros::ffi_error!();
+        let range = unsafe { ffi::otio_stack_range_in_parent(self.ptr, &mut err) };
+        if err.code != 0 {
+            return Err(OtioError::from(err));
+        }
+        Ok(time_range_from_ffi(&range))
+    }
+
+    /// Get the trimmed range of this stack in its own time space.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range cannot be computed.
+    pub fn trimmed_range(&self) -> Result<TimeRange> {
+        let mut err = macros::ffi_error!();
+        let range = unsafe { ffi::otio_stack_trimmed_range(self.ptr, &mut err) };
+        if err.code != 0 {
+            return Err(OtioError::from(err));
+        }
+        Ok(time_range_from_ffi(&range))
+    }
+
+    /// Flatten the tracks of this stack into a new standalone track.
+    ///
+    /// Higher tracks win where items overlap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stack has children that are not tracks.
+    pub fn flatten(&self) -> Result<crate::Track> {
+        let mut err = macros::ffi_error!();
+        let ptr = unsafe { ffi::otio_stack_flatten(self.ptr, &mut err) };
+        if ptr.is_null() {
+            return Err(OtioError::from(err));
+        }
+        Ok(crate::Track { ptr, owned: true })
+    }
 }
 
 crate::traits::impl_has_metadata!(
@@ -413,6 +515,34 @@ impl TrackRef<'_> {
         get_track_parent(self.ptr)
     }
 
+    /// Get the range of this track within its parent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the track has no parent or the range cannot be computed.
+    pub fn range_in_parent(&self) -> Result<TimeRange> {
+        let mut err = macros::ffi_error!();
+        let range = unsafe { ffi::otio_track_range_in_parent(self.ptr, &mut err) };
+        if err.code != 0 {
+            return Err(OtioError::from(err));
+        }
+        Ok(time_range_from_ffi(&range))
+    }
+
+    /// Get the trimmed range of this track in its own time space.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range cannot be computed.
+    pub fn trimmed_range(&self) -> Result<TimeRange> {
+        let mut err = macros::ffi_error!();
+        let range = unsafe { ffi::otio_track_trimmed_range(self.ptr, &mut err) };
+        if err.code != 0 {
+            return Err(OtioError::from(err));
+        }
+        Ok(time_range_from_ffi(&range))
+    }
+
     /// Get the kind of this track (video or audio).
     #[must_use]
     pub fn kind(&self) -> crate::TrackKind {
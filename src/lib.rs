@@ -72,6 +72,18 @@ pub use image_sequence_reference::ImageSequenceReference;
 mod time_effect;
 pub use time_effect::{FreezeFrame, LinearTimeWarp};
 
+pub mod playback_manifest;
+pub use playback_manifest::{PlaybackEntry, PlaybackManifest, PlaybackMedia};
+
+pub mod metadata_migration;
+pub use metadata_migration::{
+    ConflictPolicy, MetadataChange, MetadataMigration, MigrationOutcome, MigrationReport,
+};
+
+#[cfg(feature = "fault-injection")]
+#[doc(hidden)]
+pub mod testing;
+
 use std::ffi::{CStr, CString};
 use std::path::Path;
 
@@ -126,6 +138,28 @@ pub(crate) fn ffi_string_to_rust(ptr: *mut std::ffi::c_char) -> String {
     result
 }
 
+/// Settle ownership of a child after an FFI call that may have transferred it.
+///
+/// The child is forgotten only if the C++ side reports that it took the child
+/// (`taken != 0`), which can happen even when the call failed. Otherwise the
+/// child is dropped here and its memory released.
+pub(crate) fn finish_transfer<T>(
+    child: T,
+    taken: i32,
+    result: i32,
+    err: ffi::OtioError,
+) -> Result<()> {
+    if taken != 0 {
+        std::mem::forget(child);
+    } else {
+        drop(child);
+    }
+    if result != 0 {
+        return Err(err.into());
+    }
+    Ok(())
+}
+
 /// Check if an FFI `RationalTime` represents an unset/sentinel value.
 ///
 /// The FFI layer uses rate=1.0, value=0.0 as a sentinel for "not set".
@@ -182,6 +216,149 @@ impl RationalTime {
     pub fn to_seconds(self) -> f64 {
         self.value / self.rate
     }
+
+    /// Encode as a lossless `"value/rate"` string, e.g. `"86400/24"`.
+    ///
+    /// Both components use the shortest decimal form that parses back to
+    /// the same `f64`, so [`RationalTime::from_string_exact`] restores the
+    /// value bit-for-bit.
+    #[must_use]
+    pub fn to_string_exact(self) -> String {
+        format!("{}/{}", self.value, self.rate)
+    }
+
+    /// Decode a string produced by [`RationalTime::to_string_exact`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not of the form `"value/rate"`, the
+    /// value is not finite, or the rate is not finite and positive.
+    pub fn from_string_exact(s: &str) -> Result<Self> {
+        let parse = |part: &str| part.trim().parse::<f64>().ok().filter(|x| x.is_finite());
+        s.split_once('/')
+            .and_then(|(value, rate)| Some(Self::new(parse(value)?, parse(rate)?)))
+            .filter(|time| time.rate > 0.0)
+            .ok_or_else(|| OtioError {
+                code: 1,
+                message: format!("Invalid exact RationalTime string: '{s}'"),
+            })
+    }
+
+    /// Convert to an integer tick count at the given timebase (ticks per second).
+    ///
+    /// The conversion is done in exact integer arithmetic and rounded to the
+    /// nearest tick (halves away from zero), so equal times always map to the
+    /// same tick count regardless of their rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timebase is zero, the rate is not positive,
+    /// either component is not finite, or the result does not fit in an `i128`.
+    pub fn to_ticks(self, timebase: u64) -> Result<i128> {
+        let error = |message: &str| OtioError {
+            code: 1,
+            message: message.to_string(),
+        };
+        if timebase == 0 {
+            return Err(error("Timebase must be non-zero"));
+        }
+        if !self.value.is_finite() || !self.rate.is_finite() || self.rate <= 0.0 {
+            return Err(error("RationalTime must be finite with a positive rate"));
+        }
+
+        // value * timebase / rate, with value = mv * 2^ev and rate = mr * 2^er
+        let (mv, ev) = decompose_f64(self.value);
+        let (mr, er) = decompose_f64(self.rate);
+        let overflow = || error("Tick count overflows i128");
+        let mut num = mv.checked_mul(i128::from(timebase)).ok_or_else(overflow)?;
+        let mut den = mr;
+        let shift = ev - er;
+        let scale = 1_i128.checked_shl(shift.unsigned_abs()).filter(|s| *s > 0);
+        if shift >= 0 {
+            num = scale.and_then(|s| num.checked_mul(s)).ok_or_else(overflow)?;
+        } else {
+            match scale.and_then(|s| den.checked_mul(s)) {
+                Some(d) => den = d,
+                // The denominator exceeds any possible numerator: |ticks| < 0.5
+                None => return Ok(0),
+            }
+        }
+
+        let quotient = num / den;
+        let remainder = (num % den).abs();
+        if remainder >= den - remainder {
+            Ok(quotient + num.signum())
+        } else {
+            Ok(quotient)
+        }
+    }
+
+    /// Create a `RationalTime` at the given rate from a tick count.
+    ///
+    /// The value is an `f64` and therefore only as close to the exact time as
+    /// floating point allows, e.g. NTSC frame 9 comes back as
+    /// `9.000000000000002`. It is chosen so that [`RationalTime::to_ticks`]
+    /// returns exactly `ticks` again, which is always possible for
+    /// `|ticks| <= 2^52`. Keep the ticks when exact values matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timebase is zero, the rate is not finite and
+    /// positive, or no `f64` value at `rate` converts back to exactly `ticks`
+    /// (possible once `|ticks| > 2^52`).
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_ticks(ticks: i128, timebase: u64, rate: f64) -> Result<Self> {
+        let mut value = Self::from_seconds(ticks as f64 / timebase as f64, rate).value;
+        // The float estimate can be a few ulps off; step towards the exact value
+        for _ in 0..64 {
+            let time = Self::new(value, rate);
+            match time.to_ticks(timebase)?.cmp(&ticks) {
+                std::cmp::Ordering::Equal => return Ok(time),
+                std::cmp::Ordering::Less => value = next_f64(value, true),
+                std::cmp::Ordering::Greater => value = next_f64(value, false),
+            }
+        }
+        Err(OtioError {
+            code: 1,
+            message: format!(
+                "{ticks} ticks at timebase {timebase} cannot be represented exactly at rate {rate}"
+            ),
+        })
+    }
+}
+
+/// The adjacent `f64` above (`up`) or below a finite `x`.
+fn next_f64(x: f64, up: bool) -> f64 {
+    if x == 0.0 {
+        let tiny = f64::from_bits(1);
+        return if up { tiny } else { -tiny };
+    }
+    let bits = x.to_bits();
+    if (x > 0.0) == up {
+        f64::from_bits(bits + 1)
+    } else {
+        f64::from_bits(bits - 1)
+    }
+}
+
+/// Split a finite `f64` into `(mantissa, exponent)` with `x == mantissa * 2^exponent`.
+fn decompose_f64(x: f64) -> (i128, i32) {
+    let bits = x.to_bits();
+    let exponent_bits = i32::try_from((bits >> 52) & 0x7ff).unwrap_or(0);
+    let fraction = i128::from(bits & ((1_u64 << 52) - 1));
+    let (mut mantissa, mut exponent) = if exponent_bits == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), exponent_bits - 1075)
+    };
+    while mantissa != 0 && mantissa & 1 == 0 {
+        mantissa >>= 1;
+        exponent += 1;
+    }
+    if x.is_sign_negative() {
+        mantissa = -mantissa;
+    }
+    (mantissa, exponent)
 }
 
 impl From<RationalTime> for ffi::OtioRationalTime {
@@ -567,6 +744,33 @@ impl Timeline {
         let ptr = unsafe { ffi::otio_timeline_find_clips(self.ptr) };
         ClipSearchIter::new(ptr)
     }
+
+    /// Flatten all tracks of the given kind into a single new track.
+    ///
+    /// Higher tracks take precedence where items overlap, and gaps expose
+    /// the content of the tracks below. Content of lower tracks that is
+    /// covered by a higher track is dropped, which suits video but not audio,
+    /// where overlapping tracks are normally mixed. Nested compositions are
+    /// copied into the result unchanged rather than flattened.
+    ///
+    /// The returned track is a standalone copy; editing it does not affect
+    /// this timeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tracks cannot be flattened.
+    pub fn flatten_tracks(&self, kind: TrackKind) -> Result<Track> {
+        let kind_val = match kind {
+            TrackKind::Video => 0,
+            TrackKind::Audio => 1,
+        };
+        let mut err = macros::ffi_error!();
+        let ptr = unsafe { ffi::otio_timeline_flatten_tracks(self.ptr, kind_val, &mut err) };
+        if ptr.is_null() {
+            return Err(err.into());
+        }
+        Ok(Track { ptr, owned: true })
+    }
 }
 
 traits::impl_has_metadata!(Timeline, otio_timeline_set_metadata_string, otio_timeline_get_metadata_string);
@@ -678,15 +882,13 @@ impl Track {
     /// # Errors
     ///
     /// Returns an error if the marker cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_marker(&mut self, marker: Marker) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result = unsafe { ffi::otio_track_add_marker(self.ptr, marker.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(marker);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_track_add_marker(self.ptr, marker.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(marker, taken, result, err)
     }
 
     /// Get the number of markers on this track.
@@ -813,7 +1015,6 @@ impl Track {
     /// # Errors
     ///
     /// Returns an error if the overwrite operation fails.
-    #[allow(clippy::forget_non_drop)]
     pub fn overwrite(
         &mut self,
         clip: Clip,
@@ -821,20 +1022,18 @@ impl Track {
         remove_transitions: bool,
     ) -> Result<()> {
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_track_overwrite(
                 self.ptr,
                 clip.ptr,
                 range.into(),
                 i32::from(remove_transitions),
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(clip);
-        Ok(())
+        finish_transfer(clip, taken, result, err)
     }
 
     /// Insert a clip at a specific time, shifting subsequent items.
@@ -851,7 +1050,6 @@ impl Track {
     /// # Errors
     ///
     /// Returns an error if the insert operation fails.
-    #[allow(clippy::forget_non_drop)]
     pub fn insert_at_time(
         &mut self,
         clip: Clip,
@@ -859,20 +1057,18 @@ impl Track {
         remove_transitions: bool,
     ) -> Result<()> {
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_track_insert_at_time(
                 self.ptr,
                 clip.ptr,
                 time.into(),
                 i32::from(remove_transitions),
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(clip);
-        Ok(())
+        finish_transfer(clip, taken, result, err)
     }
 
     /// Slice (split) the track at a specific time point.
@@ -979,16 +1175,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the media reference cannot be set.
-    #[allow(clippy::forget_non_drop)] // Reference ownership transfers to C++
     pub fn set_media_reference(&mut self, reference: ExternalReference) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result =
-            unsafe { ffi::otio_clip_set_media_reference(self.ptr, reference.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference); // Clip now owns the reference - only forget on success
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_set_media_reference(self.ptr, reference.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Set a missing reference for this clip (for offline/placeholder clips).
@@ -996,16 +1189,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be set.
-    #[allow(clippy::forget_non_drop)]
     pub fn set_missing_reference(&mut self, reference: MissingReference) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result =
-            unsafe { ffi::otio_clip_set_missing_reference(self.ptr, reference.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_set_missing_reference(self.ptr, reference.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Set a generator reference for this clip (for generated content).
@@ -1013,16 +1203,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be set.
-    #[allow(clippy::forget_non_drop)]
     pub fn set_generator_reference(&mut self, reference: GeneratorReference) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result =
-            unsafe { ffi::otio_clip_set_generator_reference(self.ptr, reference.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_set_generator_reference(self.ptr, reference.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Set an image sequence reference for this clip (for VFX image sequences).
@@ -1030,20 +1217,21 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be set.
-    #[allow(clippy::forget_non_drop)]
     pub fn set_image_sequence_reference(
         &mut self,
         reference: ImageSequenceReference,
     ) -> Result<()> {
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
-            ffi::otio_clip_set_image_sequence_reference(self.ptr, reference.ptr, &mut err)
+            ffi::otio_clip_set_image_sequence_reference(
+                self.ptr,
+                reference.ptr,
+                &mut taken,
+                &mut err,
+            )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Get the available range of this clip's media.
@@ -1139,24 +1327,21 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_external_reference(&mut self, key: &str, reference: ExternalReference) -> Result<()> {
         let c_key = CString::new(key).unwrap();
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_clip_add_media_reference(
                 self.ptr,
                 c_key.as_ptr(),
                 reference.ptr.cast(),
                 0, // OTIO_REF_TYPE_EXTERNAL
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Add a missing reference with a key.
@@ -1169,24 +1354,21 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_missing_reference(&mut self, key: &str, reference: MissingReference) -> Result<()> {
         let c_key = CString::new(key).unwrap();
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_clip_add_media_reference(
                 self.ptr,
                 c_key.as_ptr(),
                 reference.ptr.cast(),
                 1, // OTIO_REF_TYPE_MISSING
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Add a generator reference with a key.
@@ -1199,24 +1381,21 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_generator_reference(&mut self, key: &str, reference: GeneratorReference) -> Result<()> {
         let c_key = CString::new(key).unwrap();
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_clip_add_media_reference(
                 self.ptr,
                 c_key.as_ptr(),
                 reference.ptr.cast(),
                 2, // OTIO_REF_TYPE_GENERATOR
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Add an image sequence reference with a key.
@@ -1229,7 +1408,6 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the reference cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_image_sequence_reference(
         &mut self,
         key: &str,
@@ -1237,20 +1415,18 @@ impl Clip {
     ) -> Result<()> {
         let c_key = CString::new(key).unwrap();
         let mut err = macros::ffi_error!();
+        let mut taken = 0;
         let result = unsafe {
             ffi::otio_clip_add_media_reference(
                 self.ptr,
                 c_key.as_ptr(),
                 reference.ptr.cast(),
                 3, // OTIO_REF_TYPE_IMAGE_SEQUENCE
+                &mut taken,
                 &mut err,
             )
         };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(reference);
-        Ok(())
+        finish_transfer(reference, taken, result, err)
     }
 
     /// Add a marker to this clip.
@@ -1258,15 +1434,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the marker cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_marker(&mut self, marker: Marker) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result = unsafe { ffi::otio_clip_add_marker(self.ptr, marker.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(marker);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_add_marker(self.ptr, marker.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(marker, taken, result, err)
     }
 
     /// Get the number of markers on this clip.
@@ -1282,15 +1456,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the effect cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_effect(&mut self, effect: Effect) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result = unsafe { ffi::otio_clip_add_effect(self.ptr, effect.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(effect);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_add_effect(self.ptr, effect.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(effect, taken, result, err)
     }
 
     /// Add a linear time warp effect to this clip.
@@ -1298,15 +1470,13 @@ impl Clip {
     /// # Errors
     ///
     /// Returns an error if the effect cannot be added.
-    #[allow(clippy::forget_non_drop)]
     pub fn add_linear_time_warp(&mut self, effect: LinearTimeWarp) -> Result<()> {
         let mut err = macros::ffi_error!();
-        let result = unsafe { ffi::otio_clip_add_linear_time_warp(self.ptr, effect.ptr, &mut err) };
-        if result != 0 {
-            return Err(err.into());
-        }
-        std::mem::forget(effect);
-        Ok(())
+        let mut taken = 0;
+        let result = unsafe {
+            ffi::otio_clip_add_linear_time_warp(self.ptr, effect.ptr, &mut taken, &mut err)
+        };
+        finish_transfer(effect, taken, result, err)
     }
 
     /// Get the number of effects on this clip.
@@ -1440,6 +1610,12 @@ impl Clip {
 
 traits::impl_has_metadata!(Clip, otio_clip_set_metadata_string, otio_clip_get_metadata_string);
 
+impl Drop for Clip {
+    fn drop(&mut self) {
+        unsafe { ffi::otio_clip_free(self.ptr) }
+    }
+}
+
 /// A gap represents empty space in a track.
 pub struct Gap {
     ptr: *mut ffi::OtioGap,
@@ -1462,6 +1638,12 @@ impl Gap {
 
 traits::impl_has_metadata!(Gap, otio_gap_set_metadata_string, otio_gap_get_metadata_string);
 
+impl Drop for Gap {
+    fn drop(&mut self) {
+        unsafe { ffi::otio_gap_free(self.ptr) }
+    }
+}
+
 /// An external reference points to a media file.
 pub struct ExternalReference {
     ptr: *mut ffi::OtioExternalRef,
@@ -1535,6 +1717,12 @@ impl ExternalReference {
 
 traits::impl_has_metadata!(ExternalReference, otio_external_ref_set_metadata_string, otio_external_ref_get_metadata_string);
 
+impl Drop for ExternalReference {
+    fn drop(&mut self) {
+        unsafe { ffi::otio_external_ref_free(self.ptr) }
+    }
+}
+
 /// A stack is a composition that layers its children.
 ///
 /// Stacks are used for:
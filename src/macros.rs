@@ -14,6 +14,8 @@ macro_rules! ffi_error {
 
 /// Implements an append method that transfers ownership to C++.
 ///
+/// Ownership is settled by `finish_transfer` from the FFI's `taken` flag.
+///
 /// # Usage
 /// ```ignore
 /// impl_append!(append_clip, Clip, otio_track_append_clip,
@@ -25,22 +27,22 @@ macro_rules! impl_append {
         ///
         /// # Errors
         ///
-        /// Returns an error if the operation fails.
-        #[allow(clippy::forget_non_drop)]
+        /// Returns an error if the operation fails. The child is freed on
+        /// failure unless the container already took ownership of it.
         pub fn $method(&mut self, child: $child_type) -> crate::Result<()> {
             let mut err = crate::macros::ffi_error!();
-            let result = unsafe { crate::ffi::$ffi_fn(self.ptr, child.ptr, &mut err) };
-            if result != 0 {
-                return Err(err.into());
-            }
-            std::mem::forget(child);
-            Ok(())
+            let mut taken = 0;
+            let result =
+                unsafe { crate::ffi::$ffi_fn(self.ptr, child.ptr, &mut taken, &mut err) };
+            crate::finish_transfer(child, taken, result, err)
         }
     };
 }
 
 /// Implements an insert method that transfers ownership to C++.
 ///
+/// Ownership is settled by `finish_transfer` from the FFI's `taken` flag.
+///
 /// # Usage
 /// ```ignore
 /// impl_insert!(insert_clip, Clip, otio_track_insert_clip,
@@ -52,19 +54,17 @@ macro_rules! impl_insert {
         ///
         /// # Errors
         ///
-        /// Returns an error if the operation fails.
-        #[allow(clippy::forget_non_drop)]
+        /// Returns an error if the operation fails. The child is freed on
+        /// failure unless the container already took ownership of it.
         #[allow(clippy::cast_possible_truncation)]
         #[allow(clippy::cast_possible_wrap)]
         pub fn $method(&mut self, index: usize, child: $child_type) -> crate::Result<()> {
             let mut err = crate::macros::ffi_error!();
-            let result =
-                unsafe { crate::ffi::$ffi_fn(self.ptr, index as i32, child.ptr, &mut err) };
-            if result != 0 {
-                return Err(err.into());
-            }
-            std::mem::forget(child);
-            Ok(())
+            let mut taken = 0;
+            let result = unsafe {
+                crate::ffi::$ffi_fn(self.ptr, index as i32, child.ptr, &mut taken, &mut err)
+            };
+            crate::finish_transfer(child, taken, result, err)
         }
     };
 }
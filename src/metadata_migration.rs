@@ -0,0 +1,267 @@
+//! Metadata namespace migration across a timeline's object graph.
+//!
+//! Vendors and tools usually store their metadata under a top-level
+//! namespace key (e.g. `"acme"` or `"fcp_xml"`). A [`MetadataMigration`]
+//! moves those namespaces to new keys on every object in a timeline and
+//! returns a [`MigrationReport`] describing what changed.
+
+use crate::{ffi, ffi_string_to_rust, macros, OtioError, Result, Timeline};
+use std::ffi::CString;
+use std::path::Path;
+
+/// Outcome constants (must match C header defines)
+const OUTCOME_RENAMED: i32 = 0;
+const OUTCOME_OVERWROTE: i32 = 1;
+const OUTCOME_SKIPPED_CONFLICT: i32 = 2;
+const OUTCOME_MERGED: i32 = 3;
+
+/// What to do when a rule's destination key already exists on an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Leave both keys untouched and record the conflict in the report.
+    #[default]
+    Skip = 0,
+    /// Replace the existing destination value with the source value.
+    ///
+    /// The whole destination value is replaced: if it is a namespace
+    /// dictionary, every key it held is lost. Use [`ConflictPolicy::Merge`]
+    /// to move keys into an existing namespace instead.
+    Overwrite = 1,
+    /// Deep-merge the source dictionary into the destination dictionary.
+    ///
+    /// Only applies when both values are dictionaries and no non-dictionary
+    /// key exists on both sides; otherwise the conflict is skipped as with
+    /// [`ConflictPolicy::Skip`], so no existing value is ever replaced.
+    Merge = 2,
+}
+
+/// The result of applying one rule to one object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationOutcome {
+    /// The source key was moved to the destination key.
+    Renamed,
+    /// The source key was moved, replacing an existing destination value.
+    Overwrote,
+    /// The destination key already existed, so nothing was changed.
+    SkippedConflict,
+    /// The source dictionary was merged into the existing destination dictionary.
+    Merged,
+}
+
+/// A single entry in a [`MigrationReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataChange {
+    /// Schema name of the object that was visited (e.g. `"Clip"`, `"Marker"`).
+    pub schema: String,
+    /// Name of the object that was visited.
+    pub object_name: String,
+    /// The metadata key the rule matched.
+    pub from_key: String,
+    /// The metadata key the rule targets.
+    pub to_key: String,
+    /// What happened.
+    pub outcome: MigrationOutcome,
+}
+
+/// The changes made by a [`MetadataMigration`] pass, in traversal order.
+///
+/// Objects whose metadata did not contain any rule's source key do not
+/// appear in the report.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    changes: Vec<MetadataChange>,
+}
+
+impl MigrationReport {
+    /// Get all recorded entries, including skipped conflicts.
+    #[must_use]
+    pub fn changes(&self) -> &[MetadataChange] {
+        &self.changes
+    }
+
+    /// Get the number of recorded entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Check whether the pass matched nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Get the number of keys that were actually moved.
+    #[must_use]
+    pub fn applied_count(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|c| c.outcome != MigrationOutcome::SkippedConflict)
+            .count()
+    }
+
+    /// Iterate over entries that were skipped because of a conflict.
+    pub fn conflicts(&self) -> impl Iterator<Item = &MetadataChange> {
+        self.changes
+            .iter()
+            .filter(|c| c.outcome == MigrationOutcome::SkippedConflict)
+    }
+}
+
+/// A configurable mapper that renames metadata namespaces across a timeline.
+///
+/// Each rule moves a top-level metadata key (and its whole value, including
+/// nested dictionaries) to a new key. The pass visits the timeline, its
+/// tracks and nested compositions, every item, marker and effect, and all
+/// media references of each clip. Rules are applied in order on each
+/// object, so `a -> b` followed by `b -> c` moves `a` to `c`.
+///
+/// # Example
+///
+/// ```no_run
+/// use otio_rs::{ConflictPolicy, MetadataMigration};
+/// use std::path::Path;
+///
+/// let migration = MetadataMigration::new()
+///     .rename("acme_vendor", "studio")
+///     .rename("legacy_notes", "studio_notes")
+///     .conflict_policy(ConflictPolicy::Skip);
+///
+/// // As a post-read hook
+/// let (timeline, report) = migration.read_from_file(Path::new("edit.otio")).unwrap();
+/// for change in report.conflicts() {
+///     println!("{} '{}': {} already set", change.schema, change.object_name, change.to_key);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MetadataMigration {
+    rules: Vec<(String, String)>,
+    conflict_policy: ConflictPolicy,
+}
+
+impl MetadataMigration {
+    /// Create a migration with no rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule moving the `from` namespace key to `to`.
+    ///
+    /// Rules where `from` and `to` are equal are ignored.
+    #[must_use]
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.rules.push((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// Set how existing destination keys are handled (defaults to [`ConflictPolicy::Skip`]).
+    #[must_use]
+    pub fn conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self
+    }
+
+    /// Get the configured `(from, to)` rules in application order.
+    #[must_use]
+    pub fn rules(&self) -> &[(String, String)] {
+        &self.rules
+    }
+
+    /// Apply the migration to every object in the timeline (standalone pass).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object graph cannot be traversed. Objects
+    /// visited before the failure keep their migrated metadata.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn apply(&self, timeline: &mut Timeline) -> Result<MigrationReport> {
+        let from_keys: Vec<CString> = self
+            .rules
+            .iter()
+            .map(|(from, _)| CString::new(from.as_str()).unwrap())
+            .collect();
+        let to_keys: Vec<CString> = self
+            .rules
+            .iter()
+            .map(|(_, to)| CString::new(to.as_str()).unwrap())
+            .collect();
+        let mut from_ptrs: Vec<*const std::ffi::c_char> =
+            from_keys.iter().map(|s| s.as_ptr()).collect();
+        let mut to_ptrs: Vec<*const std::ffi::c_char> =
+            to_keys.iter().map(|s| s.as_ptr()).collect();
+
+        let mut err = macros::ffi_error!();
+        let report = unsafe {
+            ffi::otio_timeline_migrate_metadata(
+                timeline.ptr,
+                from_ptrs.as_mut_ptr(),
+                to_ptrs.as_mut_ptr(),
+                self.rules.len() as i32,
+                self.conflict_policy as i32,
+                &mut err,
+            )
+        };
+        if report.is_null() {
+            return Err(err.into());
+        }
+
+        let count = unsafe { ffi::otio_migration_report_count(report) };
+        let mut changes = Vec::new();
+        for i in 0..count {
+            let outcome = match unsafe { ffi::otio_migration_report_outcome_at(report, i) } {
+                OUTCOME_RENAMED => MigrationOutcome::Renamed,
+                OUTCOME_OVERWROTE => MigrationOutcome::Overwrote,
+                OUTCOME_SKIPPED_CONFLICT => MigrationOutcome::SkippedConflict,
+                OUTCOME_MERGED => MigrationOutcome::Merged,
+                other => {
+                    unsafe { ffi::otio_migration_report_free(report) };
+                    return Err(OtioError {
+                        code: 1,
+                        message: format!("Unknown metadata migration outcome: {other}"),
+                    });
+                }
+            };
+            changes.push(MetadataChange {
+                schema: ffi_string_to_rust(unsafe {
+                    ffi::otio_migration_report_schema_at(report, i)
+                }),
+                object_name: ffi_string_to_rust(unsafe {
+                    ffi::otio_migration_report_object_name_at(report, i)
+                }),
+                from_key: ffi_string_to_rust(unsafe {
+                    ffi::otio_migration_report_from_key_at(report, i)
+                }),
+                to_key: ffi_string_to_rust(unsafe {
+                    ffi::otio_migration_report_to_key_at(report, i)
+                }),
+                outcome,
+            });
+        }
+        unsafe { ffi::otio_migration_report_free(report) };
+
+        Ok(MigrationReport { changes })
+    }
+
+    /// Read a timeline from a JSON file and migrate it (post-read hook).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the migration fails.
+    pub fn read_from_file(&self, path: &Path) -> Result<(Timeline, MigrationReport)> {
+        let mut timeline = Timeline::read_from_file(path)?;
+        let report = self.apply(&mut timeline)?;
+        Ok((timeline, report))
+    }
+
+    /// Deserialize a timeline from a JSON string and migrate it (post-read hook).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON cannot be parsed or the migration fails.
+    pub fn read_from_json_string(&self, json: &str) -> Result<(Timeline, MigrationReport)> {
+        let mut timeline = Timeline::from_json_string(json)?;
+        let report = self.apply(&mut timeline)?;
+        Ok((timeline, report))
+    }
+}
@@ -0,0 +1,529 @@
+//! Playback manifest export for web players.
+//!
+//! A [`PlaybackManifest`] is an ordered list of media segments describing
+//! how to play back the flattened program of one track kind: which URL to
+//! load, which portion of it to play, and at what speed.
+
+use crate::{ClipRef, Composable, OtioError, Result, TimeRange, Timeline, Track, TrackKind};
+use std::fmt::Write;
+
+/// What a playback segment shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackMedia {
+    /// Empty space (black/silence).
+    Gap,
+    /// A media file; the entry's URL is its target URL.
+    External,
+    /// An image sequence; the entry's URL has a printf-style frame number
+    /// placeholder, e.g. `"file:///shots/shot.%04d.exr"`.
+    ImageSequence,
+    /// Generated content (bars, solid color, ...); there is no URL.
+    Generator,
+    /// A clip whose media is offline, unset or of an unknown kind; there is
+    /// no URL, but the player should not treat it as intentional black.
+    Missing,
+}
+
+impl PlaybackMedia {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Gap => "gap",
+            Self::External => "external",
+            Self::ImageSequence => "image_sequence",
+            Self::Generator => "generator",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// A single segment of a playback manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackEntry {
+    /// Name of the clip (empty for gaps).
+    pub name: String,
+    /// What this segment shows.
+    pub media: PlaybackMedia,
+    /// Media URL to play, if the media has one (see [`PlaybackMedia`]).
+    pub source_url: Option<String>,
+    /// Program time in seconds at which this segment starts.
+    pub program_start: f64,
+    /// Program time in seconds at which this segment ends.
+    pub program_end: f64,
+    /// Media time in seconds at which playback of the source starts.
+    pub media_in: f64,
+    /// Media time in seconds at which playback of the source ends.
+    pub media_out: f64,
+    /// Playback speed (1.0 = normal, 0.0 = freeze frame, negative = reverse).
+    pub speed: f64,
+}
+
+impl PlaybackEntry {
+    /// Get the program duration of this segment in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.program_end - self.program_start
+    }
+
+    /// Check whether this segment is a gap.
+    #[must_use]
+    pub fn is_gap(&self) -> bool {
+        self.media == PlaybackMedia::Gap
+    }
+
+    fn gap(program_start: f64, program_end: f64) -> Self {
+        Self {
+            name: String::new(),
+            media: PlaybackMedia::Gap,
+            source_url: None,
+            program_start,
+            program_end,
+            media_in: 0.0,
+            media_out: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    /// The part of this segment between `start` and `end` in program time.
+    fn slice(&self, start: f64, end: f64) -> Self {
+        let media_in = self.media_in + (start - self.program_start) * self.speed;
+        Self {
+            program_start: start,
+            program_end: end,
+            media_in,
+            media_out: media_in + (end - start) * self.speed,
+            ..self.clone()
+        }
+    }
+
+    /// The parts of this segment outside `start..end` in program time.
+    fn without(self, start: f64, end: f64) -> Vec<Self> {
+        if end <= self.program_start || start >= self.program_end {
+            return vec![self];
+        }
+        let mut parts = Vec::new();
+        if start > self.program_start {
+            parts.push(self.slice(self.program_start, start));
+        }
+        if end < self.program_end {
+            parts.push(self.slice(end, self.program_end));
+        }
+        parts
+    }
+}
+
+/// An ordered playback manifest for the flattened program of one track kind.
+///
+/// # Example
+///
+/// ```no_run
+/// use otio_rs::{PlaybackManifest, Timeline, TrackKind};
+/// use std::path::Path;
+///
+/// let timeline = Timeline::read_from_file(Path::new("edit.otio")).unwrap();
+/// let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+///
+/// std::fs::write("program.json", manifest.to_json()).unwrap();
+/// std::fs::write("program.m3u8", manifest.to_m3u()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackManifest {
+    /// The kind of tracks this manifest was built from.
+    pub kind: TrackKind,
+    /// Segments in program order.
+    pub entries: Vec<PlaybackEntry>,
+}
+
+impl PlaybackManifest {
+    /// Build a manifest from all tracks of the given kind in a timeline.
+    ///
+    /// The tracks are flattened first (see [`Timeline::flatten_tracks`]), so
+    /// higher tracks win where items overlap. Nested stacks and tracks are
+    /// expanded as described in [`PlaybackManifest::from_track`].
+    ///
+    /// Audio tracks are mixed rather than layered, so flattening them would
+    /// silently drop the lower track wherever two of them play at once. For
+    /// [`TrackKind::Audio`] this returns an error in that case; use
+    /// [`PlaybackManifest::from_timeline_per_track`] to get one manifest per
+    /// track instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tracks cannot be flattened, a clip's range
+    /// cannot be computed, or audio overlaps between tracks or within a
+    /// nested stack.
+    pub fn from_timeline(timeline: &Timeline, kind: TrackKind) -> Result<Self> {
+        if kind == TrackKind::Audio {
+            let tracks: Vec<Vec<PlaybackEntry>> = Self::from_timeline_per_track(timeline, kind)?
+                .into_iter()
+                .map(|manifest| manifest.entries)
+                .collect();
+            if let Some(time) = first_overlap(&tracks) {
+                return Err(OtioError {
+                    code: 1,
+                    message: format!(
+                        "Audio tracks overlap at {time:.3}s; flattening would drop the \
+                         lower track (use PlaybackManifest::from_timeline_per_track)"
+                    ),
+                });
+            }
+        }
+        let flat = timeline.flatten_tracks(kind)?;
+        Self::from_track(&flat)
+    }
+
+    /// Build one manifest per track of the given kind, in timeline order.
+    ///
+    /// Nothing is flattened across tracks, so this is the way to export
+    /// audio tracks that play simultaneously and must be mixed by the player.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a clip's range cannot be computed, or if audio
+    /// overlaps within a nested stack.
+    pub fn from_timeline_per_track(timeline: &Timeline, kind: TrackKind) -> Result<Vec<Self>> {
+        let tracks = match kind {
+            TrackKind::Video => timeline.video_tracks(),
+            TrackKind::Audio => timeline.audio_tracks(),
+        };
+        tracks
+            .map(|track| {
+                let mut entries = Vec::new();
+                collect_entries(track.children(), Window::FULL, kind, &mut entries)?;
+                Ok(Self { kind, entries })
+            })
+            .collect()
+    }
+
+    /// Build a manifest from a single track.
+    ///
+    /// Clips become media segments and gaps become gap segments. Nested
+    /// tracks are expanded in place, limited to the part of them visible in
+    /// the parent. The children of a nested stack (tracks or items) are
+    /// layered with the topmost child winning, like
+    /// [`Timeline::flatten_tracks`]; for an audio track, audio that overlaps
+    /// within a nested stack is an error instead. Transitions are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a clip's range cannot be computed, or if audio
+    /// overlaps within a nested stack of an audio track.
+    pub fn from_track(track: &Track) -> Result<Self> {
+        let kind = track.kind();
+        let mut entries = Vec::new();
+        collect_entries(track.children(), Window::FULL, kind, &mut entries)?;
+        Ok(Self { kind, entries })
+    }
+
+    /// Get the total program duration in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.entries.last().map_or(0.0, |e| e.program_end)
+    }
+
+    /// Serialize the manifest to JSON.
+    ///
+    /// ```text
+    /// {"kind":"video","duration":2,"entries":[
+    ///   {"name":"A","media":"external","url":"/media/a.mov",
+    ///    "start":0,"end":2,"in":10,"out":12,"speed":1}]}
+    /// ```
+    ///
+    /// `media` is one of `gap`, `external`, `image_sequence`, `generator` or
+    /// `missing`. Entries without a URL have a `null` URL. Non-finite numbers
+    /// are written as `null`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"kind\":");
+        push_json_string(&mut out, kind_name(self.kind));
+        let _ = write!(out, ",\"duration\":{},\"entries\":[", json_number(self.duration()));
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"name\":");
+            push_json_string(&mut out, &entry.name);
+            out.push_str(",\"media\":");
+            push_json_string(&mut out, entry.media.name());
+            out.push_str(",\"url\":");
+            match &entry.source_url {
+                Some(url) => push_json_string(&mut out, url),
+                None => out.push_str("null"),
+            }
+            let _ = write!(
+                out,
+                ",\"start\":{},\"end\":{},\"in\":{},\"out\":{},\"speed\":{}}}",
+                json_number(entry.program_start),
+                json_number(entry.program_end),
+                json_number(entry.media_in),
+                json_number(entry.media_out),
+                json_number(entry.speed),
+            );
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// Serialize the manifest to an HLS-like M3U playlist.
+    ///
+    /// Each segment is written as an `#EXTINF` duration followed by an
+    /// `#EXT-X-OTIO-RANGE` tag carrying the media in/out points and speed.
+    /// Media other than plain files is marked with `#EXT-X-OTIO-MEDIA`, and
+    /// entries without a URL get the media name as a placeholder URI. Gaps are
+    /// marked with `#EXT-X-GAP`. Line breaks in names and URLs are neutralised
+    /// so they cannot inject playlist lines.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn to_m3u(&self) -> String {
+        let target_duration = self
+            .entries
+            .iter()
+            .map(PlaybackEntry::duration)
+            .fold(0.0_f64, f64::max)
+            .ceil() as u64;
+
+        let mut out = String::new();
+        out.push_str("#EXTM3U\n");
+        out.push_str("#EXT-X-PLAYLIST-TYPE:VOD\n");
+        let _ = writeln!(out, "#EXT-X-TARGETDURATION:{target_duration}");
+        for entry in &self.entries {
+            let title = entry.name.replace(['\r', '\n'], " ");
+            let _ = writeln!(out, "#EXTINF:{:.3},{title}", entry.duration());
+            if entry.is_gap() {
+                out.push_str("#EXT-X-GAP\ngap\n");
+                continue;
+            }
+            if entry.media != PlaybackMedia::External {
+                let _ = writeln!(
+                    out,
+                    "#EXT-X-OTIO-MEDIA:{}",
+                    entry.media.name().to_ascii_uppercase().replace('_', "-")
+                );
+            }
+            let _ = writeln!(
+                out,
+                "#EXT-X-OTIO-RANGE:IN={:.3},OUT={:.3},SPEED={}",
+                entry.media_in, entry.media_out, entry.speed
+            );
+            match &entry.source_url {
+                // Percent-encode line breaks so the URI stays on one line
+                Some(url) => out.push_str(&url.replace('\r', "%0D").replace('\n', "%0A")),
+                None => out.push_str(entry.media.name()),
+            }
+            out.push('\n');
+        }
+        out.push_str("#EXT-X-ENDLIST\n");
+        out
+    }
+}
+
+/// Maps a composition's own time (in seconds) to program time, limited to
+/// the part of the composition that is visible in the program.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    shift: f64,
+    start: f64,
+    end: f64,
+}
+
+impl Window {
+    const FULL: Self = Self {
+        shift: 0.0,
+        start: f64::NEG_INFINITY,
+        end: f64::INFINITY,
+    };
+
+    /// The window of a nested composition occupying `in_parent` in this
+    /// window's time space and showing `trimmed` of its own time space.
+    fn nested(self, in_parent: TimeRange, trimmed: TimeRange) -> Self {
+        let own_start = trimmed.start_time.to_seconds();
+        let offset = in_parent.start_time.to_seconds() - own_start;
+        Self {
+            shift: self.shift + offset,
+            start: (self.start - offset).max(own_start),
+            end: (self.end - offset).min(own_start + trimmed.duration.to_seconds()),
+        }
+    }
+
+    /// The visible part of `range`, or `None` if it is entirely hidden.
+    fn visible(self, range: TimeRange) -> Option<(f64, f64)> {
+        let start = range.start_time.to_seconds().max(self.start);
+        let end = range.end_time().to_seconds().min(self.end);
+        (end > start).then_some((start, end))
+    }
+}
+
+fn collect_entries<'a>(
+    children: impl Iterator<Item = Composable<'a>>,
+    window: Window,
+    kind: TrackKind,
+    entries: &mut Vec<PlaybackEntry>,
+) -> Result<()> {
+    for child in children {
+        match child {
+            Composable::Clip(clip) => {
+                let range = clip.range_in_parent()?;
+                let Some((start, end)) = window.visible(range) else {
+                    continue;
+                };
+                let speed = clip.time_scalar();
+                let media_in = clip.trimmed_range()?.start_time.to_seconds()
+                    + (start - range.start_time.to_seconds()) * speed;
+                let (media, source_url) = clip_media(&clip);
+                entries.push(PlaybackEntry {
+                    name: clip.name(),
+                    media,
+                    source_url,
+                    program_start: start + window.shift,
+                    program_end: end + window.shift,
+                    media_in,
+                    media_out: media_in + (end - start) * speed,
+                    speed,
+                });
+            }
+            Composable::Gap(gap) => {
+                let Some((start, end)) = window.visible(gap.range_in_parent()?) else {
+                    continue;
+                };
+                entries.push(PlaybackEntry::gap(start + window.shift, end + window.shift));
+            }
+            Composable::Stack(stack) => {
+                let nested = window.nested(stack.range_in_parent()?, stack.trimmed_range()?);
+                if nested.end <= nested.start {
+                    continue;
+                }
+                // Every child of a stack is a layer; later children are on top
+                let layers = stack
+                    .children()
+                    .map(|layer| {
+                        let mut layer_entries = Vec::new();
+                        collect_entries(std::iter::once(layer), nested, kind, &mut layer_entries)?;
+                        Ok(layer_entries)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                if kind == TrackKind::Audio {
+                    if let Some(time) = first_overlap(&layers) {
+                        return Err(OtioError {
+                            code: 1,
+                            message: format!(
+                                "Audio overlaps at {time:.3}s in nested stack '{}'; \
+                                 a single manifest cannot mix it",
+                                stack.name()
+                            ),
+                        });
+                    }
+                }
+                entries.extend(composite(
+                    layers,
+                    nested.start + nested.shift,
+                    nested.end + nested.shift,
+                ));
+            }
+            Composable::Track(track) => {
+                let nested = window.nested(track.range_in_parent()?, track.trimmed_range()?);
+                if nested.end > nested.start {
+                    collect_entries(track.children(), nested, kind, entries)?;
+                }
+            }
+            Composable::Transition(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// The media kind and URL of a clip's active media reference.
+fn clip_media(clip: &ClipRef<'_>) -> (PlaybackMedia, Option<String>) {
+    match clip.media_reference_type() {
+        // OTIO_REF_TYPE_EXTERNAL
+        0 => (PlaybackMedia::External, clip.target_url()),
+        // OTIO_REF_TYPE_GENERATOR
+        2 => (PlaybackMedia::Generator, None),
+        // OTIO_REF_TYPE_IMAGE_SEQUENCE
+        3 => (PlaybackMedia::ImageSequence, clip.image_sequence_url()),
+        _ => (PlaybackMedia::Missing, None),
+    }
+}
+
+/// Composite stack layers (bottom to top) so that the topmost media wins,
+/// filling whatever no layer covers between `start` and `end` with gaps.
+fn composite(layers: Vec<Vec<PlaybackEntry>>, start: f64, end: f64) -> Vec<PlaybackEntry> {
+    let mut shown: Vec<PlaybackEntry> = Vec::new();
+    for layer in layers.into_iter().rev() {
+        for entry in layer.into_iter().filter(|e| !e.is_gap()) {
+            let mut pieces = vec![entry];
+            for above in &shown {
+                pieces = pieces
+                    .into_iter()
+                    .flat_map(|piece| piece.without(above.program_start, above.program_end))
+                    .collect();
+            }
+            shown.extend(pieces);
+        }
+    }
+    shown.sort_by(|a, b| a.program_start.total_cmp(&b.program_start));
+
+    let mut entries = Vec::with_capacity(shown.len());
+    let mut time = start;
+    for entry in shown {
+        if entry.program_start > time {
+            entries.push(PlaybackEntry::gap(time, entry.program_start));
+        }
+        time = entry.program_end;
+        entries.push(entry);
+    }
+    if end > time {
+        entries.push(PlaybackEntry::gap(time, end));
+    }
+    entries
+}
+
+/// Find the first program time at which media of two layers plays at once.
+fn first_overlap(layers: &[Vec<PlaybackEntry>]) -> Option<f64> {
+    let mut first: Option<f64> = None;
+    for (i, a) in layers.iter().enumerate() {
+        for b in &layers[i + 1..] {
+            for x in a.iter().filter(|e| !e.is_gap()) {
+                for y in b.iter().filter(|e| !e.is_gap()) {
+                    if x.program_start < y.program_end && y.program_start < x.program_end {
+                        let time = x.program_start.max(y.program_start);
+                        first = Some(first.map_or(time, |f| f.min(time)));
+                    }
+                }
+            }
+        }
+    }
+    first
+}
+
+fn kind_name(kind: TrackKind) -> &'static str {
+    match kind {
+        TrackKind::Video => "video",
+        TrackKind::Audio => "audio",
+    }
+}
+
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        format!("{value}")
+    } else {
+        "null".to_string()
+    }
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,34 @@
+//! Hooks for exercising FFI failure paths in tests.
+//!
+//! Only available with the `fault-injection` feature. These are not part of
+//! the supported API and may change at any time.
+
+use crate::ffi;
+
+/// Mirrors `OTIO_TRANSFER_FAULT_NONE` in the shim.
+const NO_TRANSFER_FAULT: i32 = 0;
+
+/// Where an injected ownership-transfer failure happens.
+///
+/// Values mirror the `OTIO_TRANSFER_FAULT_*` defines in the shim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum TransferFault {
+    /// Fail before the child is handed to the container.
+    Before = 1,
+    /// Fail after the container has already retained the child.
+    After = 2,
+}
+
+/// Make the next ownership-transfer call (append, insert, edit, add marker,
+/// set media reference, ...) on the current thread fail.
+///
+/// The fault is consumed by that call, whatever its outcome.
+pub fn inject_transfer_fault(fault: TransferFault) {
+    unsafe { ffi::otio_debug_inject_transfer_fault(fault as i32) };
+}
+
+/// Cancel a pending injected fault on the current thread.
+pub fn clear_transfer_fault() {
+    unsafe { ffi::otio_debug_inject_transfer_fault(NO_TRANSFER_FAULT) };
+}
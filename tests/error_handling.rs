@@ -215,3 +215,19 @@ fn test_error_debug_impl() {
         "Debug should include type name"
     );
 }
+
+// ============================================================================
+// Ownership Transfer Failure Tests
+// ============================================================================
+
+#[test]
+fn test_append_already_parented_track() {
+    let mut timeline = Timeline::new("Test");
+    let track = timeline.add_video_track("V1");
+    let mut stack = Stack::new("Other");
+
+    // A real (not injected) failure: the track already belongs to the timeline
+    assert!(stack.append_track(track).is_err());
+    assert_eq!(stack.children_count(), 0);
+    assert_eq!(timeline.video_tracks().count(), 1);
+}
@@ -11,6 +11,8 @@
 // Intentional drops to test memory cleanup
 #![allow(clippy::drop_non_drop)]
 
+#[cfg(feature = "fault-injection")]
+use otio_rs::testing::{inject_transfer_fault, TransferFault};
 use otio_rs::{
     marker, Clip, Gap, HasMetadata, ImageSequenceReference, Marker, RationalTime, Stack,
     Timeline, TimeRange, Track,
@@ -202,6 +204,62 @@ fn stress_test_stacks() {
     }
 }
 
+/// Stress test: Failed appends and inserts must neither leak nor double-free.
+#[cfg(feature = "fault-injection")]
+#[test]
+#[ignore = "Run with memory tools: cargo test --test memory -- --ignored"]
+fn stress_test_failed_transfers() {
+    for iteration in 0..1000 {
+        let mut stack = Stack::new(&format!("Stack {iteration}"));
+        let mut track = Track::new_video("V1");
+
+        // Rejected children are freed by their Rust destructors
+        inject_transfer_fault(TransferFault::Before);
+        let _ = track.append_clip(Clip::new(
+            "Rejected",
+            TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(24.0, 24.0)),
+        ));
+        inject_transfer_fault(TransferFault::Before);
+        let _ = track.insert_gap(0, Gap::new(RationalTime::new(24.0, 24.0)));
+        let mut clip = Clip::new(
+            "Annotated",
+            TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(24.0, 24.0)),
+        );
+        inject_transfer_fault(TransferFault::Before);
+        let _ = clip.add_marker(Marker::with_default_color(
+            "Rejected",
+            TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(1.0, 24.0)),
+        ));
+        inject_transfer_fault(TransferFault::Before);
+        let _ = clip.set_image_sequence_reference(ImageSequenceReference::new(
+            "file:///shots/",
+            "shot.",
+            ".exr",
+            1001,
+            1,
+            24.0,
+            4,
+        ));
+
+        // Retained children are freed by their new parent
+        inject_transfer_fault(TransferFault::After);
+        let _ = track.append_clip(Clip::new(
+            "Retained",
+            TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(24.0, 24.0)),
+        ));
+        inject_transfer_fault(TransferFault::After);
+        let _ = clip.add_marker(Marker::with_default_color(
+            "Retained",
+            TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(1.0, 24.0)),
+        ));
+        let _ = track.append_clip(clip);
+        inject_transfer_fault(TransferFault::After);
+        let _ = stack.append_track(track);
+
+        assert_eq!(stack.children_count(), 1);
+    }
+}
+
 /// Stress test: `ImageSequenceReference` creation.
 #[test]
 #[ignore = "Run with memory tools: cargo test --test memory -- --ignored"]
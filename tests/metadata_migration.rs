@@ -0,0 +1,214 @@
+//! Tests for metadata namespace migration.
+
+use otio_rs::{
+    Clip, ConflictPolicy, ExternalReference, HasMetadata, Marker, MetadataMigration,
+    MigrationOutcome, RationalTime, Stack, TimeRange, Timeline,
+};
+
+fn make_range() -> TimeRange {
+    TimeRange::new(RationalTime::new(0.0, 24.0), RationalTime::new(48.0, 24.0))
+}
+
+/// Build a timeline with vendor metadata on the timeline, track, clip,
+/// marker, media reference and a nested stack.
+fn vendor_timeline() -> Timeline {
+    let mut timeline = Timeline::new("Migration Test");
+    timeline.set_metadata("acme", "timeline-value");
+
+    let mut track = timeline.add_video_track("V1");
+    track.set_metadata("acme", "track-value");
+
+    let mut clip = Clip::new("Clip A", make_range());
+    clip.set_metadata("acme", "clip-value");
+
+    let mut media_ref = ExternalReference::new("/media/a.mov");
+    media_ref.set_metadata("acme", "ref-value");
+    clip.set_media_reference(media_ref).unwrap();
+
+    let mut marker = Marker::with_default_color("Note", make_range());
+    marker.set_metadata("acme", "marker-value");
+    clip.add_marker(marker).unwrap();
+    track.append_clip(clip).unwrap();
+
+    let mut stack = Stack::new("Nested");
+    stack.set_metadata("acme", "stack-value");
+    stack.append_clip(Clip::new("Clip B", make_range())).unwrap();
+    track.append_stack(stack).unwrap();
+
+    timeline
+}
+
+#[test]
+fn test_migration_renames_across_graph() {
+    let mut timeline = vendor_timeline();
+
+    let report = MetadataMigration::new()
+        .rename("acme", "studio")
+        .apply(&mut timeline)
+        .unwrap();
+
+    // Timeline, root stack has none, track, clip, marker, media ref, nested stack
+    assert_eq!(report.len(), 6);
+    assert_eq!(report.applied_count(), 6);
+    assert!(report
+        .changes()
+        .iter()
+        .all(|c| c.from_key == "acme" && c.to_key == "studio"));
+    assert!(report.changes().iter().any(|c| c.schema == "Marker"));
+    assert!(report.changes().iter().any(|c| c.schema == "ExternalReference"));
+
+    assert_eq!(timeline.get_metadata("acme"), None);
+    assert_eq!(timeline.get_metadata("studio"), Some("timeline-value".to_string()));
+
+    let clip = timeline.find_clips().find(|c| c.name() == "Clip A").unwrap();
+    assert_eq!(clip.get_metadata("acme"), None);
+    assert_eq!(clip.get_metadata("studio"), Some("clip-value".to_string()));
+
+    let json = timeline.to_json_string().unwrap();
+    assert!(!json.contains("\"acme\""));
+    assert!(json.contains("marker-value"));
+    assert!(json.contains("ref-value"));
+}
+
+#[test]
+fn test_migration_conflict_skip() {
+    let mut timeline = Timeline::new("Conflict Test");
+    timeline.set_metadata("acme", "old");
+    timeline.set_metadata("studio", "existing");
+
+    let report = MetadataMigration::new()
+        .rename("acme", "studio")
+        .apply(&mut timeline)
+        .unwrap();
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(report.applied_count(), 0);
+    assert_eq!(report.conflicts().count(), 1);
+    assert_eq!(report.changes()[0].schema, "Timeline");
+    assert_eq!(report.changes()[0].object_name, "Conflict Test");
+
+    // Both keys are left untouched
+    assert_eq!(timeline.get_metadata("acme"), Some("old".to_string()));
+    assert_eq!(timeline.get_metadata("studio"), Some("existing".to_string()));
+}
+
+#[test]
+fn test_migration_conflict_overwrite() {
+    let mut timeline = Timeline::new("Overwrite Test");
+    timeline.set_metadata("acme", "new");
+    timeline.set_metadata("studio", "existing");
+
+    let report = MetadataMigration::new()
+        .rename("acme", "studio")
+        .conflict_policy(ConflictPolicy::Overwrite)
+        .apply(&mut timeline)
+        .unwrap();
+
+    assert_eq!(report.changes()[0].outcome, MigrationOutcome::Overwrote);
+    assert_eq!(timeline.get_metadata("acme"), None);
+    assert_eq!(timeline.get_metadata("studio"), Some("new".to_string()));
+}
+
+#[test]
+fn test_migration_rules_apply_in_order() {
+    let mut timeline = Timeline::new("Chain Test");
+    timeline.set_metadata("a", "value");
+
+    let report = MetadataMigration::new()
+        .rename("a", "b")
+        .rename("b", "c")
+        .rename("same", "same")
+        .apply(&mut timeline)
+        .unwrap();
+
+    assert_eq!(report.len(), 2);
+    assert_eq!(timeline.get_metadata("a"), None);
+    assert_eq!(timeline.get_metadata("b"), None);
+    assert_eq!(timeline.get_metadata("c"), Some("value".to_string()));
+}
+
+#[test]
+fn test_migration_moves_nested_namespace() {
+    let json = r#"{
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": "Nested Namespace",
+        "metadata": {"acme": {"reel": "A001", "take": 3}},
+        "tracks": {"OTIO_SCHEMA": "Stack.1", "children": []}
+    }"#;
+
+    let (timeline, report) = MetadataMigration::new()
+        .rename("acme", "studio")
+        .read_from_json_string(json)
+        .unwrap();
+    assert_eq!(report.applied_count(), 1);
+
+    let out: serde_json::Value =
+        serde_json::from_str(&timeline.to_json_string().unwrap()).unwrap();
+    assert!(out["metadata"].get("acme").is_none());
+    assert_eq!(out["metadata"]["studio"]["reel"], "A001");
+    assert_eq!(out["metadata"]["studio"]["take"], 3);
+}
+
+#[test]
+fn test_migration_conflict_merge() {
+    let json = r#"{
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": "Merge Test",
+        "metadata": {
+            "acme": {"reel": "A001", "color": {"lut": "show.cube"}},
+            "studio": {"shot": "010", "color": {"cdl": "grade.cdl"}},
+            "legacy": {"shot": "999"},
+            "flat": "value",
+            "existing_flat": "keep"
+        },
+        "tracks": {"OTIO_SCHEMA": "Stack.1", "children": []}
+    }"#;
+
+    let (timeline, report) = MetadataMigration::new()
+        .rename("acme", "studio")
+        .rename("legacy", "studio")
+        .rename("flat", "existing_flat")
+        .conflict_policy(ConflictPolicy::Merge)
+        .read_from_json_string(json)
+        .unwrap();
+
+    let outcomes: Vec<MigrationOutcome> = report.changes().iter().map(|c| c.outcome).collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            MigrationOutcome::Merged,
+            MigrationOutcome::SkippedConflict,
+            MigrationOutcome::SkippedConflict,
+        ]
+    );
+    assert_eq!(report.applied_count(), 1);
+
+    let out: serde_json::Value =
+        serde_json::from_str(&timeline.to_json_string().unwrap()).unwrap();
+    let meta = &out["metadata"];
+    // Disjoint keys are merged, nested dictionaries are merged recursively
+    assert!(meta.get("acme").is_none());
+    assert_eq!(meta["studio"]["shot"], "010");
+    assert_eq!(meta["studio"]["reel"], "A001");
+    assert_eq!(meta["studio"]["color"]["cdl"], "grade.cdl");
+    assert_eq!(meta["studio"]["color"]["lut"], "show.cube");
+    // A leaf collision or non-dictionary destination leaves both keys untouched
+    assert_eq!(meta["legacy"]["shot"], "999");
+    assert_eq!(meta["flat"], "value");
+    assert_eq!(meta["existing_flat"], "keep");
+}
+
+#[test]
+fn test_migration_no_matches() {
+    let mut timeline = vendor_timeline();
+
+    let report = MetadataMigration::new()
+        .rename("unknown_vendor", "studio")
+        .apply(&mut timeline)
+        .unwrap();
+    assert!(report.is_empty());
+
+    let empty = MetadataMigration::new().apply(&mut timeline).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(timeline.get_metadata("acme"), Some("timeline-value".to_string()));
+}
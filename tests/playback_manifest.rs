@@ -0,0 +1,401 @@
+//! Tests for flattening and playback manifest export.
+
+// Allow exact float comparisons in tests - values are known exactly
+#![allow(clippy::float_cmp)]
+
+use otio_rs::{
+    Clip, Composable, ExternalReference, Gap, GeneratorReference, ImageSequenceReference,
+    LinearTimeWarp, PlaybackManifest, PlaybackMedia, RationalTime, Stack, TimeRange, Timeline,
+    Track, TrackKind,
+};
+
+fn bare_clip(name: &str, start: f64, frames: f64) -> Clip {
+    Clip::new(
+        name,
+        TimeRange::new(RationalTime::new(start, 24.0), RationalTime::new(frames, 24.0)),
+    )
+}
+
+fn media_clip(name: &str, url: &str, start: f64, frames: f64) -> Clip {
+    let mut clip = bare_clip(name, start, frames);
+    clip.set_media_reference(ExternalReference::new(url)).unwrap();
+    clip
+}
+
+/// V1 holds a 2 second clip; V2 covers its second half with another clip.
+fn layered_timeline() -> Timeline {
+    let mut timeline = Timeline::new("Layered");
+
+    let mut v1 = timeline.add_video_track("V1");
+    v1.append_clip(media_clip("A", "/media/a.mov", 0.0, 48.0)).unwrap();
+
+    let mut v2 = timeline.add_video_track("V2");
+    v2.append_gap(Gap::new(RationalTime::new(24.0, 24.0))).unwrap();
+    v2.append_clip(media_clip("B", "/media/b.mov", 240.0, 24.0)).unwrap();
+
+    timeline
+}
+
+#[test]
+fn test_flatten_tracks_upper_track_wins() {
+    let timeline = layered_timeline();
+    let flat = timeline.flatten_tracks(TrackKind::Video).unwrap();
+
+    assert_eq!(flat.kind(), TrackKind::Video);
+    let names: Vec<String> = flat
+        .children()
+        .filter_map(|c| match c {
+            Composable::Clip(clip) => Some(clip.name()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, vec!["A", "B"]);
+
+    // The timeline itself is untouched
+    assert_eq!(timeline.video_tracks().count(), 2);
+}
+
+#[test]
+fn test_manifest_from_layered_timeline() {
+    let timeline = layered_timeline();
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+
+    assert_eq!(manifest.kind, TrackKind::Video);
+    assert_eq!(manifest.entries.len(), 2);
+    assert_eq!(manifest.duration(), 2.0);
+
+    let a = &manifest.entries[0];
+    assert_eq!(a.source_url.as_deref(), Some("/media/a.mov"));
+    assert_eq!((a.program_start, a.program_end), (0.0, 1.0));
+    assert_eq!((a.media_in, a.media_out), (0.0, 1.0));
+    assert_eq!(a.speed, 1.0);
+
+    let b = &manifest.entries[1];
+    assert_eq!(b.source_url.as_deref(), Some("/media/b.mov"));
+    assert_eq!((b.program_start, b.program_end), (1.0, 2.0));
+    assert_eq!((b.media_in, b.media_out), (10.0, 11.0));
+}
+
+#[test]
+fn test_manifest_speed_from_time_warp() {
+    let mut timeline = Timeline::new("Speed");
+    let mut track = timeline.add_video_track("V1");
+    let mut clip = media_clip("Fast", "/media/fast.mov", 0.0, 48.0);
+    clip.add_linear_time_warp(LinearTimeWarp::new("2x", 2.0)).unwrap();
+    track.append_clip(clip).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let entry = &manifest.entries[0];
+    assert_eq!(entry.speed, 2.0);
+    assert_eq!(entry.duration(), 2.0);
+    assert_eq!((entry.media_in, entry.media_out), (0.0, 4.0));
+}
+
+#[test]
+fn test_manifest_respects_track_kind() {
+    let mut timeline = layered_timeline();
+    let mut audio = timeline.add_audio_track("A1");
+    audio.append_clip(media_clip("Music", "/media/music.wav", 0.0, 72.0)).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Audio).unwrap();
+    assert_eq!(manifest.kind, TrackKind::Audio);
+    assert_eq!(manifest.entries.len(), 1);
+    assert_eq!(manifest.entries[0].source_url.as_deref(), Some("/media/music.wav"));
+    assert_eq!(manifest.duration(), 3.0);
+}
+
+#[test]
+fn test_manifest_rejects_overlapping_audio() {
+    let mut timeline = Timeline::new("Mix");
+    let mut dialogue = timeline.add_audio_track("Dialogue");
+    dialogue.append_clip(media_clip("Line", "/media/line.wav", 0.0, 48.0)).unwrap();
+    let mut music = timeline.add_audio_track("Music");
+    music.append_gap(Gap::new(RationalTime::new(24.0, 24.0))).unwrap();
+    music.append_clip(media_clip("Score", "/media/score.wav", 0.0, 48.0)).unwrap();
+
+    // Flattening would drop the dialogue under the music, so refuse
+    let err = PlaybackManifest::from_timeline(&timeline, TrackKind::Audio).unwrap_err();
+    assert!(err.message.contains("overlap at 1.000s"), "{}", err.message);
+
+    let per_track = PlaybackManifest::from_timeline_per_track(&timeline, TrackKind::Audio).unwrap();
+    assert_eq!(per_track.len(), 2);
+    assert_eq!(per_track[0].entries.len(), 1);
+    assert_eq!(per_track[0].entries[0].source_url.as_deref(), Some("/media/line.wav"));
+    assert_eq!(per_track[0].duration(), 2.0);
+    assert_eq!(per_track[1].entries.len(), 2);
+    assert!(per_track[1].entries[0].is_gap());
+    assert_eq!(per_track[1].entries[1].source_url.as_deref(), Some("/media/score.wav"));
+    assert_eq!(per_track[1].duration(), 3.0);
+}
+
+#[test]
+fn test_manifest_expands_nested_stack() {
+    let mut timeline = Timeline::new("Nested");
+    let mut v1 = timeline.add_video_track("V1");
+    v1.append_clip(media_clip("A", "/media/a.mov", 0.0, 24.0)).unwrap();
+
+    // A nested stack whose upper track covers the second half of the lower one
+    let mut lower = Track::new_video("Lower");
+    lower.append_clip(media_clip("B", "/media/b.mov", 240.0, 48.0)).unwrap();
+    let mut upper = Track::new_video("Upper");
+    upper.append_gap(Gap::new(RationalTime::new(24.0, 24.0))).unwrap();
+    upper.append_clip(media_clip("C", "/media/c.mov", 0.0, 24.0)).unwrap();
+    let mut stack = Stack::new("Nested");
+    stack.append_track(lower).unwrap();
+    stack.append_track(upper).unwrap();
+    v1.append_stack(stack).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let urls: Vec<Option<&str>> =
+        manifest.entries.iter().map(|e| e.source_url.as_deref()).collect();
+    assert_eq!(
+        urls,
+        vec![Some("/media/a.mov"), Some("/media/b.mov"), Some("/media/c.mov")]
+    );
+    assert_eq!(manifest.duration(), 3.0);
+
+    let b = &manifest.entries[1];
+    assert_eq!((b.program_start, b.program_end), (1.0, 2.0));
+    assert_eq!((b.media_in, b.media_out), (10.0, 11.0));
+    let c = &manifest.entries[2];
+    assert_eq!((c.program_start, c.program_end), (2.0, 3.0));
+    assert_eq!((c.media_in, c.media_out), (0.0, 1.0));
+}
+
+#[test]
+fn test_manifest_rejects_overlapping_audio_in_nested_stack() {
+    let mut timeline = Timeline::new("Stems");
+    let mut a1 = timeline.add_audio_track("A1");
+
+    // Both stems of the nested stack play during the second second
+    let mut dialogue = Track::new_audio("Dialogue");
+    dialogue.append_clip(media_clip("Line", "/media/line.wav", 0.0, 48.0)).unwrap();
+    let mut music = Track::new_audio("Music");
+    music.append_gap(Gap::new(RationalTime::new(24.0, 24.0))).unwrap();
+    music.append_clip(media_clip("Score", "/media/score.wav", 0.0, 24.0)).unwrap();
+    let mut stack = Stack::new("Stems");
+    stack.append_track(dialogue).unwrap();
+    stack.append_track(music).unwrap();
+    a1.append_stack(stack).unwrap();
+
+    let err = PlaybackManifest::from_timeline(&timeline, TrackKind::Audio).unwrap_err();
+    assert!(err.message.contains("at 1.000s"), "{}", err.message);
+    assert!(err.message.contains("'Stems'"), "{}", err.message);
+
+    let err = PlaybackManifest::from_timeline_per_track(&timeline, TrackKind::Audio).unwrap_err();
+    assert!(err.message.contains("'Stems'"), "{}", err.message);
+}
+
+#[test]
+fn test_manifest_stack_of_clips_topmost_wins() {
+    let mut timeline = Timeline::new("Clip Stack");
+    let mut v1 = timeline.add_video_track("V1");
+
+    // A stack holding clips directly: B sits on top of the first half of A
+    let mut stack = Stack::new("Comp");
+    stack.append_clip(media_clip("A", "/media/a.mov", 0.0, 48.0)).unwrap();
+    stack.append_clip(media_clip("B", "/media/b.mov", 240.0, 24.0)).unwrap();
+    v1.append_stack(stack).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let names: Vec<&str> = manifest.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["B", "A"]);
+    assert_eq!(manifest.duration(), 2.0);
+
+    let b = &manifest.entries[0];
+    assert_eq!((b.program_start, b.program_end), (0.0, 1.0));
+    assert_eq!((b.media_in, b.media_out), (10.0, 11.0));
+    let a = &manifest.entries[1];
+    assert_eq!((a.program_start, a.program_end), (1.0, 2.0));
+    assert_eq!((a.media_in, a.media_out), (1.0, 2.0));
+}
+
+#[test]
+fn test_manifest_trims_nested_stack() {
+    // The nested stack only shows seconds 1-2 of its 3 second track
+    let json = r#"{
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": "Trimmed Nested",
+        "tracks": {"OTIO_SCHEMA": "Stack.1", "children": [{
+            "OTIO_SCHEMA": "Track.1",
+            "name": "V1",
+            "kind": "Video",
+            "children": [
+                {"OTIO_SCHEMA": "Gap.1", "source_range": {"OTIO_SCHEMA": "TimeRange.1",
+                    "start_time": {"OTIO_SCHEMA": "RationalTime.1", "value": 0, "rate": 24},
+                    "duration": {"OTIO_SCHEMA": "RationalTime.1", "value": 24, "rate": 24}}},
+                {"OTIO_SCHEMA": "Stack.1", "name": "Nested",
+                    "source_range": {"OTIO_SCHEMA": "TimeRange.1",
+                        "start_time": {"OTIO_SCHEMA": "RationalTime.1", "value": 24, "rate": 24},
+                        "duration": {"OTIO_SCHEMA": "RationalTime.1", "value": 24, "rate": 24}},
+                    "children": [{
+                        "OTIO_SCHEMA": "Track.1", "name": "Inner", "kind": "Video",
+                        "children": [{
+                            "OTIO_SCHEMA": "Clip.2", "name": "X",
+                            "source_range": {"OTIO_SCHEMA": "TimeRange.1",
+                                "start_time": {"OTIO_SCHEMA": "RationalTime.1", "value": 0, "rate": 24},
+                                "duration": {"OTIO_SCHEMA": "RationalTime.1", "value": 72, "rate": 24}},
+                            "media_references": {"DEFAULT_MEDIA": {
+                                "OTIO_SCHEMA": "ExternalReference.1", "target_url": "/media/x.mov"}},
+                            "active_media_reference_key": "DEFAULT_MEDIA"
+                        }]
+                    }]
+                }
+            ]
+        }]}
+    }"#;
+    let timeline = Timeline::from_json_string(json).unwrap();
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+
+    assert_eq!(manifest.entries.len(), 2);
+    assert!(manifest.entries[0].is_gap());
+    let x = &manifest.entries[1];
+    assert_eq!(x.source_url.as_deref(), Some("/media/x.mov"));
+    assert_eq!((x.program_start, x.program_end), (1.0, 2.0));
+    assert_eq!((x.media_in, x.media_out), (1.0, 2.0));
+}
+
+#[test]
+fn test_manifest_gaps_and_json() {
+    let mut timeline = Timeline::new("Gaps");
+    let mut track = timeline.add_video_track("V1");
+    track.append_gap(Gap::new(RationalTime::new(12.0, 24.0))).unwrap();
+    track.append_clip(media_clip("Say \"hi\"", "/media/c.mov", 0.0, 24.0)).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    assert!(manifest.entries[0].is_gap());
+    assert!(!manifest.entries[1].is_gap());
+
+    let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+    assert_eq!(json["kind"], "video");
+    assert_eq!(json["duration"], 1.5);
+    assert!(json["entries"][0]["url"].is_null());
+    assert_eq!(json["entries"][1]["name"], "Say \"hi\"");
+    assert_eq!(json["entries"][1]["url"], "/media/c.mov");
+    assert_eq!(json["entries"][1]["start"], 0.5);
+    assert_eq!(json["entries"][1]["out"], 1.0);
+}
+
+#[test]
+fn test_manifest_m3u() {
+    let timeline = layered_timeline();
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let m3u = manifest.to_m3u();
+
+    let lines: Vec<&str> = m3u.lines().collect();
+    assert_eq!(lines[0], "#EXTM3U");
+    assert!(lines.contains(&"#EXT-X-TARGETDURATION:1"));
+    assert!(lines.contains(&"#EXTINF:1.000,A"));
+    assert!(lines.contains(&"#EXT-X-OTIO-RANGE:IN=10.000,OUT=11.000,SPEED=1"));
+    assert!(lines.contains(&"/media/b.mov"));
+    assert_eq!(*lines.last().unwrap(), "#EXT-X-ENDLIST");
+}
+
+#[test]
+fn test_manifest_empty_timeline() {
+    let timeline = Timeline::new("Empty");
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    assert!(manifest.entries.is_empty());
+    assert_eq!(manifest.duration(), 0.0);
+    assert_eq!(manifest.to_json(), r#"{"kind":"video","duration":0,"entries":[]}"#);
+}
+
+#[test]
+fn test_manifest_image_sequence_clip() {
+    let mut timeline = Timeline::new("Sequence");
+    let mut track = timeline.add_video_track("V1");
+    let mut clip = bare_clip("Shot", 0.0, 24.0);
+    clip.set_image_sequence_reference(ImageSequenceReference::new(
+        "file:///shots", "shot.", ".exr", 1001, 1, 24.0, 4,
+    ))
+    .unwrap();
+    track.append_clip(clip).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let entry = &manifest.entries[0];
+    assert!(!entry.is_gap());
+    assert_eq!(entry.media, PlaybackMedia::ImageSequence);
+    assert_eq!(entry.source_url.as_deref(), Some("file:///shots/shot.%04d.exr"));
+
+    let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+    assert_eq!(json["entries"][0]["media"], "image_sequence");
+    assert_eq!(json["entries"][0]["url"], "file:///shots/shot.%04d.exr");
+
+    let m3u = manifest.to_m3u();
+    let lines: Vec<&str> = m3u.lines().collect();
+    assert!(lines.contains(&"#EXT-X-OTIO-MEDIA:IMAGE-SEQUENCE"));
+    assert!(lines.contains(&"file:///shots/shot.%04d.exr"));
+    assert!(!lines.contains(&"#EXT-X-GAP"));
+}
+
+#[test]
+fn test_manifest_generator_clip() {
+    let mut timeline = Timeline::new("Bars");
+    let mut track = timeline.add_video_track("V1");
+    let mut clip = bare_clip("Bars", 0.0, 24.0);
+    clip.set_generator_reference(GeneratorReference::new("Bars", "SMPTEBars")).unwrap();
+    track.append_clip(clip).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let entry = &manifest.entries[0];
+    assert!(!entry.is_gap());
+    assert_eq!(entry.media, PlaybackMedia::Generator);
+    assert_eq!(entry.source_url, None);
+
+    let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+    assert_eq!(json["entries"][0]["media"], "generator");
+    assert!(json["entries"][0]["url"].is_null());
+
+    let lines: Vec<String> = manifest.to_m3u().lines().map(String::from).collect();
+    assert!(lines.contains(&"#EXT-X-OTIO-MEDIA:GENERATOR".to_string()));
+    assert!(!lines.contains(&"#EXT-X-GAP".to_string()));
+}
+
+#[test]
+fn test_manifest_missing_reference_clip() {
+    let mut timeline = Timeline::new("Offline");
+    let mut track = timeline.add_video_track("V1");
+    track.append_clip(bare_clip("Offline", 0.0, 24.0)).unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let entry = &manifest.entries[0];
+    assert!(!entry.is_gap());
+    assert_eq!(entry.media, PlaybackMedia::Missing);
+    assert_eq!(entry.source_url, None);
+
+    let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+    assert_eq!(json["entries"][0]["media"], "missing");
+
+    let lines: Vec<String> = manifest.to_m3u().lines().map(String::from).collect();
+    assert!(lines.contains(&"#EXT-X-OTIO-MEDIA:MISSING".to_string()));
+    assert!(!lines.contains(&"#EXT-X-GAP".to_string()));
+}
+
+#[test]
+fn test_manifest_overlap_counts_non_file_audio() {
+    let mut timeline = Timeline::new("Tone");
+    let mut dialogue = timeline.add_audio_track("Dialogue");
+    dialogue.append_clip(media_clip("Line", "/media/line.wav", 0.0, 48.0)).unwrap();
+    let mut tone = timeline.add_audio_track("Tone");
+    let mut clip = bare_clip("Tone", 0.0, 24.0);
+    clip.set_generator_reference(GeneratorReference::new("Tone", "Sine")).unwrap();
+    tone.append_clip(clip).unwrap();
+
+    let err = PlaybackManifest::from_timeline(&timeline, TrackKind::Audio).unwrap_err();
+    assert!(err.message.contains("overlap at 0.000s"), "{}", err.message);
+}
+
+#[test]
+fn test_manifest_m3u_escapes_url_line_breaks() {
+    let mut timeline = Timeline::new("Injection");
+    let mut track = timeline.add_video_track("V1");
+    track
+        .append_clip(media_clip("A", "/media/a.mov\n#EXT-X-ENDLIST", 0.0, 24.0))
+        .unwrap();
+
+    let manifest = PlaybackManifest::from_timeline(&timeline, TrackKind::Video).unwrap();
+    let m3u = manifest.to_m3u();
+    let lines: Vec<&str> = m3u.lines().collect();
+    assert!(lines.contains(&"/media/a.mov%0A#EXT-X-ENDLIST"));
+    assert_eq!(lines.iter().filter(|l| **l == "#EXT-X-ENDLIST").count(), 1);
+}
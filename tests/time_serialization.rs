@@ -0,0 +1,130 @@
+//! Tests for lossless `RationalTime` string and tick encodings.
+
+use otio_rs::RationalTime;
+
+/// Flicks: 705,600,000 ticks per second, divisible by all common frame rates.
+const FLICKS: u64 = 705_600_000;
+
+#[test]
+fn test_string_exact_integer_values() {
+    let time = RationalTime::new(86400.0, 24.0);
+    assert_eq!(time.to_string_exact(), "86400/24");
+    assert_eq!(RationalTime::from_string_exact("86400/24").unwrap(), time);
+}
+
+#[test]
+fn test_string_exact_roundtrip_is_lossless() {
+    let times = [
+        RationalTime::new(1001.0, 24000.0 / 1001.0),
+        RationalTime::new(0.1 + 0.2, 30000.0 / 1001.0),
+        RationalTime::new(-12.5, 48000.0),
+        RationalTime::new(f64::MIN_POSITIVE, 1.0),
+        RationalTime::new(1e300, 25.0),
+    ];
+    for time in times {
+        let encoded = time.to_string_exact();
+        let decoded = RationalTime::from_string_exact(&encoded).unwrap();
+        assert_eq!(decoded.value.to_bits(), time.value.to_bits(), "{encoded}");
+        assert_eq!(decoded.rate.to_bits(), time.rate.to_bits(), "{encoded}");
+    }
+}
+
+#[test]
+fn test_string_exact_rejects_malformed() {
+    let inputs = [
+        "", "86400", "86400/", "/24", "abc/24", "1/2/3", "NaN/NaN", "NaN/24", "inf/24", "-inf/24",
+        "inf/0", "1/inf", "1/0", "1/-24",
+    ];
+    for input in inputs {
+        let result = RationalTime::from_string_exact(input);
+        assert!(result.is_err(), "{input:?} should not parse");
+    }
+}
+
+#[test]
+fn test_ticks_same_instant_different_rates() {
+    let a = RationalTime::new(48.0, 24.0);
+    let b = RationalTime::new(96.0, 48.0);
+    let c = RationalTime::new(96000.0, 48000.0);
+    assert_eq!(a.to_ticks(FLICKS).unwrap(), 2 * i128::from(FLICKS));
+    assert_eq!(a.to_ticks(FLICKS).unwrap(), b.to_ticks(FLICKS).unwrap());
+    assert_eq!(a.to_ticks(FLICKS).unwrap(), c.to_ticks(FLICKS).unwrap());
+}
+
+#[test]
+fn test_ticks_ntsc_rate() {
+    // One NTSC frame is exactly 1001/24000 seconds
+    let frame = RationalTime::new(1.0, 24000.0 / 1001.0);
+    assert_eq!(frame.to_ticks(24000).unwrap(), 1001);
+
+    let hour = RationalTime::new(86400.0, 24.0);
+    assert_eq!(hour.to_ticks(FLICKS).unwrap(), 3600 * i128::from(FLICKS));
+}
+
+#[test]
+fn test_ticks_rounding() {
+    assert_eq!(RationalTime::new(1.0, 3.0).to_ticks(1000).unwrap(), 333);
+    assert_eq!(RationalTime::new(2.0, 3.0).to_ticks(1000).unwrap(), 667);
+    assert_eq!(RationalTime::new(-2.0, 3.0).to_ticks(1000).unwrap(), -667);
+    // Exactly half a tick rounds away from zero
+    assert_eq!(RationalTime::new(1.0, 2000.0).to_ticks(1000).unwrap(), 1);
+    assert_eq!(RationalTime::new(-1.0, 2000.0).to_ticks(1000).unwrap(), -1);
+    assert_eq!(RationalTime::new(1e-300, 24.0).to_ticks(1000).unwrap(), 0);
+}
+
+#[test]
+fn test_ticks_errors() {
+    assert!(RationalTime::new(1.0, 24.0).to_ticks(0).is_err());
+    assert!(RationalTime::new(1.0, 0.0).to_ticks(1000).is_err());
+    assert!(RationalTime::new(1.0, -24.0).to_ticks(1000).is_err());
+    assert!(RationalTime::new(f64::NAN, 24.0).to_ticks(1000).is_err());
+    assert!(RationalTime::new(f64::INFINITY, 24.0).to_ticks(1000).is_err());
+    assert!(RationalTime::new(1e300, 24.0).to_ticks(FLICKS).is_err());
+}
+
+#[test]
+fn test_ticks_roundtrip() {
+    let time = RationalTime::new(86400.0, 24.0);
+    let ticks = time.to_ticks(FLICKS).unwrap();
+    assert_eq!(RationalTime::from_ticks(ticks, FLICKS, 24.0).unwrap(), time);
+}
+
+#[test]
+fn test_ticks_roundtrip_ntsc() {
+    // 24000/1001 is not exactly representable, so the value itself is lossy...
+    let rate = 24000.0 / 1001.0;
+    let frame = RationalTime::from_ticks(9 * 1001, 24000, rate).unwrap();
+    assert_ne!(frame.value.to_bits(), 9.0_f64.to_bits());
+    assert!((frame.value - 9.0).abs() < 1e-9);
+
+    // ...but the tick count always survives the round trip
+    for frame in 0..100_000_i128 {
+        let ticks = frame * 1001;
+        let time = RationalTime::from_ticks(ticks, 24000, rate).unwrap();
+        assert_eq!(time.to_ticks(24000).unwrap(), ticks, "frame {frame}");
+    }
+}
+
+#[test]
+fn test_ticks_roundtrip_precision_bound() {
+    let rate = 24000.0 / 1001.0;
+    let bound = 1_i128 << 52;
+
+    // Up to 2^52 ticks the round trip is exact
+    for ticks in [bound, bound - 1, bound - 12345, -bound, -(bound - 1)] {
+        let time = RationalTime::from_ticks(ticks, FLICKS, rate).unwrap();
+        assert_eq!(time.to_ticks(FLICKS).unwrap(), ticks, "{ticks}");
+    }
+
+    // Beyond it, ticks that no f64 value can reach are rejected rather than drifting
+    for ticks in [(1_i128 << 53) + 1, (1_i128 << 55) + 12345, (1_i128 << 62) + 7] {
+        assert!(RationalTime::from_ticks(ticks, FLICKS, rate).is_err(), "{ticks}");
+    }
+}
+
+#[test]
+fn test_from_ticks_errors() {
+    assert!(RationalTime::from_ticks(24, 0, 24.0).is_err());
+    assert!(RationalTime::from_ticks(24, 24, 0.0).is_err());
+    assert!(RationalTime::from_ticks(24, 24, f64::NAN).is_err());
+}
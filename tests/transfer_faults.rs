@@ -0,0 +1,193 @@
+//! Tests for ownership transfers that fail part way.
+//!
+//! Failures are injected through `otio_rs::testing`, so these tests only run
+//! with the `fault-injection` feature:
+//!
+//! Run with: `cargo test --features fault-injection --test transfer_faults`
+
+#![cfg(feature = "fault-injection")]
+
+use otio_rs::testing::{clear_transfer_fault, inject_transfer_fault, TransferFault};
+use otio_rs::{
+    Clip, Composable, Effect, ExternalReference, Gap, LinearTimeWarp, Marker, RationalTime,
+    Stack, Timeline, TimeRange, Track,
+};
+
+fn make_time_range(start: f64, duration: f64, rate: f64) -> TimeRange {
+    TimeRange::new(
+        RationalTime::new(start, rate),
+        RationalTime::new(duration, rate),
+    )
+}
+
+fn child_names(track: &Track) -> Vec<String> {
+    track
+        .children()
+        .map(|c| match c {
+            Composable::Clip(clip) => clip.name(),
+            Composable::Gap(_) => "<gap>".to_string(),
+            _ => "<other>".to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_append_fails_before_transfer() {
+    let mut timeline = Timeline::new("Test");
+    let mut track = timeline.add_video_track("V1");
+
+    inject_transfer_fault(TransferFault::Before);
+    let clip = Clip::new("Rejected", make_time_range(0.0, 24.0, 24.0));
+    let err = track.append_clip(clip).unwrap_err();
+    assert!(err.message.contains("Injected failure before transfer"));
+    assert_eq!(track.children_count(), 0);
+
+    // The fault is one-shot; the next append succeeds
+    let clip = Clip::new("Accepted", make_time_range(0.0, 24.0, 24.0));
+    track.append_clip(clip).unwrap();
+    assert_eq!(child_names(&track), vec!["Accepted"]);
+}
+
+#[test]
+fn test_append_fails_after_transfer() {
+    let mut timeline = Timeline::new("Test");
+    let mut track = timeline.add_video_track("V1");
+
+    inject_transfer_fault(TransferFault::After);
+    let clip = Clip::new("Retained", make_time_range(0.0, 24.0, 24.0));
+    let err = track.append_clip(clip).unwrap_err();
+    assert!(err.message.contains("Injected failure after transfer"));
+
+    // The track kept the clip, so it must still be alive and readable
+    assert_eq!(child_names(&track), vec!["Retained"]);
+    let json = timeline.to_json_string().unwrap();
+    assert!(json.contains("Retained"));
+}
+
+#[test]
+fn test_insert_fails_before_and_after_transfer() {
+    let mut timeline = Timeline::new("Test");
+    let mut track = timeline.add_video_track("V1");
+    track
+        .append_clip(Clip::new("A", make_time_range(0.0, 24.0, 24.0)))
+        .unwrap();
+
+    inject_transfer_fault(TransferFault::Before);
+    assert!(track.insert_gap(0, Gap::new(RationalTime::new(12.0, 24.0))).is_err());
+    assert_eq!(child_names(&track), vec!["A"]);
+
+    inject_transfer_fault(TransferFault::After);
+    assert!(track.insert_gap(0, Gap::new(RationalTime::new(12.0, 24.0))).is_err());
+    assert_eq!(child_names(&track), vec!["<gap>", "A"]);
+}
+
+#[test]
+fn test_stack_append_track_fails_after_transfer() {
+    let mut stack = Stack::new("Stack");
+
+    inject_transfer_fault(TransferFault::Before);
+    assert!(stack.append_track(Track::new_video("Dropped")).is_err());
+    assert_eq!(stack.children_count(), 0);
+
+    inject_transfer_fault(TransferFault::After);
+    assert!(stack.append_track(Track::new_video("Kept")).is_err());
+    assert_eq!(stack.children_count(), 1);
+    // Dropping the stack frees the retained track exactly once
+    drop(stack);
+}
+
+#[test]
+fn test_edit_algorithm_fails_after_transfer() {
+    let mut timeline = Timeline::new("Test");
+    let mut track = timeline.add_video_track("V1");
+    track
+        .append_clip(Clip::new("A", make_time_range(0.0, 48.0, 24.0)))
+        .unwrap();
+
+    inject_transfer_fault(TransferFault::Before);
+    let clip = Clip::new("B", make_time_range(0.0, 24.0, 24.0));
+    assert!(track.overwrite(clip, make_time_range(0.0, 24.0, 24.0), false).is_err());
+    assert_eq!(child_names(&track), vec!["A"]);
+
+    inject_transfer_fault(TransferFault::After);
+    let clip = Clip::new("C", make_time_range(0.0, 24.0, 24.0));
+    assert!(track.insert_at_time(clip, RationalTime::new(0.0, 24.0), false).is_err());
+    assert!(child_names(&track).contains(&"C".to_string()));
+}
+
+#[test]
+fn test_transfer_fault_is_thread_local() {
+    inject_transfer_fault(TransferFault::Before);
+
+    let handle = std::thread::spawn(|| {
+        let mut track = Track::new_video("Other Thread");
+        track.append_gap(Gap::new(RationalTime::new(24.0, 24.0)))
+    });
+    assert!(handle.join().unwrap().is_ok());
+
+    clear_transfer_fault();
+    let mut track = Track::new_video("This Thread");
+    assert!(track.append_gap(Gap::new(RationalTime::new(24.0, 24.0))).is_ok());
+}
+
+#[test]
+fn test_add_marker_fails_before_and_after_transfer() {
+    let mut clip = Clip::new("Clip", make_time_range(0.0, 24.0, 24.0));
+
+    inject_transfer_fault(TransferFault::Before);
+    let marker = Marker::with_default_color("Dropped", make_time_range(0.0, 1.0, 24.0));
+    assert!(clip.add_marker(marker).is_err());
+    assert_eq!(clip.markers_count(), 0);
+
+    inject_transfer_fault(TransferFault::After);
+    let marker = Marker::with_default_color("Kept", make_time_range(0.0, 1.0, 24.0));
+    assert!(clip.add_marker(marker).is_err());
+    assert_eq!(clip.markers_count(), 1);
+
+    let mut track = Track::new_video("V1");
+    inject_transfer_fault(TransferFault::After);
+    let marker = Marker::with_default_color("Track Marker", make_time_range(0.0, 1.0, 24.0));
+    assert!(track.add_marker(marker).is_err());
+    assert_eq!(track.markers_count(), 1);
+}
+
+#[test]
+fn test_add_effect_fails_before_and_after_transfer() {
+    let mut clip = Clip::new("Clip", make_time_range(0.0, 24.0, 24.0));
+
+    inject_transfer_fault(TransferFault::Before);
+    assert!(clip.add_effect(Effect::new("Blur", "Blur")).is_err());
+    inject_transfer_fault(TransferFault::Before);
+    assert!(clip.add_linear_time_warp(LinearTimeWarp::new("2x", 2.0)).is_err());
+    assert_eq!(clip.effects_count(), 0);
+
+    inject_transfer_fault(TransferFault::After);
+    assert!(clip.add_effect(Effect::new("Blur", "Blur")).is_err());
+    inject_transfer_fault(TransferFault::After);
+    assert!(clip.add_linear_time_warp(LinearTimeWarp::new("2x", 2.0)).is_err());
+    assert_eq!(clip.effects_count(), 2);
+}
+
+#[test]
+fn test_set_media_reference_fails_before_and_after_transfer() {
+    let mut timeline = Timeline::new("Test");
+    let mut track = timeline.add_video_track("V1");
+    let mut clip = Clip::new("Clip", make_time_range(0.0, 24.0, 24.0));
+
+    inject_transfer_fault(TransferFault::Before);
+    assert!(clip.add_external_reference("alt", ExternalReference::new("/media/alt.mov")).is_err());
+    assert!(!clip.has_media_reference("alt"));
+
+    inject_transfer_fault(TransferFault::After);
+    assert!(clip.add_external_reference("alt", ExternalReference::new("/media/alt.mov")).is_err());
+    assert!(clip.has_media_reference("alt"));
+
+    inject_transfer_fault(TransferFault::After);
+    assert!(clip.set_media_reference(ExternalReference::new("/media/main.mov")).is_err());
+    track.append_clip(clip).unwrap();
+
+    // Both retained references are still alive and owned by the clip
+    let json = timeline.to_json_string().unwrap();
+    assert!(json.contains("/media/alt.mov"));
+    assert!(json.contains("/media/main.mov"));
+}